@@ -34,6 +34,7 @@ use crate::binding::binding::ClassFieldInitialValue;
 use crate::binding::binding::ExprOrBinding;
 use crate::binding::binding::KeyClassField;
 use crate::binding::binding::KeyClassSynthesizedFields;
+use crate::common::symbol_kind::SymbolKind;
 use crate::error::collector::ErrorCollector;
 use crate::error::context::TypeCheckContext;
 use crate::error::context::TypeCheckKind;
@@ -42,17 +43,20 @@ use crate::python::dunder;
 use crate::types::annotation::Annotation;
 use crate::types::annotation::Qualifier;
 use crate::types::callable::BoolKeywords;
+use crate::types::callable::Callable;
 use crate::types::callable::DataclassKeywords;
 use crate::types::callable::FuncMetadata;
 use crate::types::callable::Function;
 use crate::types::callable::FunctionKind;
 use crate::types::callable::Param;
+use crate::types::callable::ParamList;
 use crate::types::callable::Required;
 use crate::types::class::Class;
 use crate::types::class::ClassType;
 use crate::types::literal::Lit;
 use crate::types::literal::LitEnum;
 use crate::types::quantified::Quantified;
+use crate::types::simplify::unions;
 use crate::types::typed_dict::TypedDict;
 use crate::types::typed_dict::TypedDictField;
 use crate::types::types::BoundMethod;
@@ -354,6 +358,31 @@ impl ClassField {
         }
     }
 
+    /// How an IDE should present this field: a `Method` for a function defined in the class
+    /// body, a `Property`, a `Variable` for an explicit `ClassVar`, and otherwise an `Attribute`.
+    pub fn symbol_kind(&self) -> SymbolKind {
+        let ty = self.raw_type();
+        if ty.is_property_getter() {
+            SymbolKind::Property
+        } else if self.is_class_var() {
+            SymbolKind::Variable
+        } else if matches!(self.initialization(), ClassFieldInitialization::Class(_))
+            && matches!(
+                ty,
+                Type::Function(_)
+                    | Type::Overload(_)
+                    | Type::Forall(box Forall {
+                        body: Forallable::Function(_),
+                        ..
+                    })
+            )
+        {
+            SymbolKind::Method
+        } else {
+            SymbolKind::Attribute
+        }
+    }
+
     pub fn has_explicit_annotation(&self) -> bool {
         match &self.0 {
             ClassFieldInner::Simple { annotation, .. } => annotation.is_some(),
@@ -1461,4 +1490,62 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
             Arc::unwrap_or_clone(attr.value).as_special_method_type(&Instance::of_class(metaclass))
         }
     }
+
+    /// The type of the class `cls` when it is used as a callable, per
+    /// https://typing.python.org/en/latest/spec/constructors.html#converting-a-constructor-to-callable.
+    pub fn constructor_to_callable(&self, cls: &ClassType) -> Option<Type> {
+        let class_type = cls.clone().to_type();
+        if let Some(mut metaclass_call_attr_ty) = self.get_metaclass_dunder_call(cls) {
+            // If the class has a custom metaclass and the return type of the metaclass's __call__
+            // is not a subclass of the current class, use that and ignore __new__ and __init__
+            if metaclass_call_attr_ty
+                .callable_return_type()
+                .is_some_and(|ret| !self.is_compatible_constructor_return(&ret, cls.class_object()))
+            {
+                return Some(metaclass_call_attr_ty);
+            }
+        }
+        // Default constructor that takes no args and returns Self.
+        let default_constructor = || {
+            Type::Callable(Box::new(Callable::list(
+                ParamList::new(Vec::new()),
+                class_type.clone(),
+            )))
+        };
+        // Check the __new__ method and whether it comes from object or has been overridden
+        let (new_attr_ty, overrides_new) = if let Some(mut t) = self
+            .get_dunder_new(cls)
+            .and_then(|t| t.to_unbound_callable())
+        {
+            if t.callable_return_type()
+                .is_some_and(|ret| !self.is_compatible_constructor_return(&ret, cls.class_object()))
+            {
+                // If the return type of __new__ is not a subclass of the current class, use that and ignore __init__
+                return Some(t);
+            }
+            (t, true)
+        } else {
+            (default_constructor(), false)
+        };
+        // Check the __init__ method and whether it comes from object or has been overridden
+        let (init_attr_ty, overrides_init) = if let Some(mut t) = self.get_dunder_init(cls, false) {
+            // Replace the return type with Self (the current class)
+            t.set_callable_return_type(class_type.clone());
+            (t, true)
+        } else {
+            (default_constructor(), false)
+        };
+        if !overrides_new && overrides_init {
+            // If `__init__` is overridden and `__new__` is inherited from object, use `__init__`
+            Some(init_attr_ty)
+        } else if overrides_new && !overrides_init {
+            // If `__new__` is overridden and `__init__` is inherited from object, use `__new__`
+            Some(new_attr_ty)
+        } else {
+            let result = unions(vec![new_attr_ty, init_attr_ty]);
+            // If both are overridden, take the union
+            // Only if neither are overridden, use the `__new__` and `__init__` from object
+            Some(result)
+        }
+    }
 }
@@ -5,6 +5,7 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use pyrefly_util::prelude::VecExt;
 use ruff_python_ast::name::Name;
 use starlark_map::small_set::SmallSet;
 use starlark_map::smallmap;
@@ -63,6 +64,11 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
     }
 
     pub fn named_tuple_element_types(&self, cls: &ClassType) -> Option<Vec<Type>> {
+        Some(self.named_tuple_elements(cls)?.into_map(|(_, ty)| ty))
+    }
+
+    /// The names and types of the elements of a named tuple, in order.
+    pub fn named_tuple_elements(&self, cls: &ClassType) -> Option<Vec<(Name, Type)>> {
         let class_metadata = self.get_metadata_for_class(cls.class_object());
         let named_tuple_metadata = class_metadata.named_tuple_metadata()?;
         Some(
@@ -71,7 +77,7 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                 .iter()
                 .filter_map(|name| {
                     let attr = self.try_lookup_attr_from_class_type(cls.clone(), name)?;
-                    self.resolve_named_tuple_element(attr)
+                    Some((name.clone(), self.resolve_named_tuple_element(attr)?))
                 })
                 .collect(),
         )
@@ -96,6 +96,30 @@ impl<'a> TypeOrExpr<'a> {
 }
 
 impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
+    /// Whether an inferred concrete tuple of this length should be widened to `tuple[T, ...]`.
+    /// Huge fixed-length tuple types (e.g. from repeated concatenation) are rarely useful, but
+    /// are expensive to build and check against. We never widen when there is a hint, as the
+    /// tuple is about to be checked against it, and widening would make a matching tuple fail.
+    pub fn should_widen_tuple(&self, len: usize, hint: Option<&Type>) -> bool {
+        hint.is_none() && len > self.solver().max_concrete_tuple_length()
+    }
+
+    /// Widen elements to a homogeneous tuple, like `should_widen_tuple` asks for.
+    pub fn widened_tuple(&self, elts: &[Type]) -> Type {
+        Type::Tuple(Tuple::unbounded(
+            self.unions(elts.map(|t| t.clone().promote_literals(self.stdlib))),
+        ))
+    }
+
+    /// Create a concrete tuple type, widening it to `tuple[T, ...]` if it is too long.
+    pub fn concrete_tuple(&self, elts: Vec<Type>, hint: Option<&Type>) -> Type {
+        if self.should_widen_tuple(elts.len(), hint) {
+            self.widened_tuple(&elts)
+        } else {
+            Type::tuple(elts)
+        }
+    }
+
     // Helper method for inferring the type of a boolean operation over a sequence of values.
     fn boolop(&self, values: &[Expr], op: BoolOp, errors: &ErrorCollector) -> Type {
         let target = match op {
@@ -747,7 +771,7 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
         })
     }
 
-    fn expr_infer_type_info_with_hint(
+    pub fn expr_infer_type_info_with_hint(
         &self,
         x: &Expr,
         hint: Option<&Type>,
@@ -892,7 +916,7 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                     &xs[0],
                     range,
                     errors,
-                    Some(&|| ErrorContext::Index(self.for_display(base.clone()))),
+                    Some(&|| ErrorContext::TupleIndex(self.for_display(base.clone()))),
                 ),
                 Type::Tuple(_) if xs.len() == 1 => self.call_method_or_error(
                     &base,
@@ -901,7 +925,7 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                     &[CallArg::expr(slice)],
                     &[],
                     errors,
-                    Some(&|| ErrorContext::Index(self.for_display(base.clone()))),
+                    Some(&|| ErrorContext::TupleIndex(self.for_display(base.clone()))),
                 ),
                 Type::Any(style) => style.propagate(),
                 Type::Literal(Lit::Bytes(ref bytes)) => self.subscript_bytes_literal(
@@ -923,7 +947,7 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                         slice,
                         range,
                         errors,
-                        Some(&|| ErrorContext::Index(self.for_display(base.clone()))),
+                        Some(&|| ErrorContext::TupleIndex(self.for_display(base.clone()))),
                     )
                 }
                 Type::ClassType(_) | Type::SelfType(_) => self.call_method_or_error(
@@ -1022,7 +1046,7 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                 }
             }
             Expr::BoolOp(x) => self.boolop(&x.values, x.op, errors),
-            Expr::BinOp(x) => self.binop_infer(x, errors),
+            Expr::BinOp(x) => self.binop_infer(x, hint, errors),
             Expr::UnaryOp(x) => self.unop_infer(x, errors),
             Expr::Lambda(lambda) => {
                 let mut param_vars = Vec::new();
@@ -1132,7 +1156,7 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                     Type::any_error()
                 } else {
                     match unbounded.as_slice() {
-                        [] => Type::tuple(prefix),
+                        [] => self.concrete_tuple(prefix, hint),
                         [middle] => Type::Tuple(Tuple::unpacked(prefix, middle.clone(), suffix)),
                         // We can't precisely model unpacking two unbounded iterables, so we'll keep any
                         // concrete prefix and suffix elements and merge everything in between into an unbounded tuple
@@ -462,6 +462,11 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
             metadata: metadata.clone(),
         })
         .forall(self.validated_tparams(def.range, tparams, errors));
+        let mut decorator_steps = if decorators.is_empty() {
+            Vec::new()
+        } else {
+            vec![ty.clone()]
+        };
         for x in decorators.into_iter().rev() {
             ty = match self.apply_decorator(*x, ty, errors) {
                 // Preserve function metadata, so things like method binding still work.
@@ -496,12 +501,14 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                     }
                 }
                 t => t,
-            }
+            };
+            decorator_steps.push(ty.clone());
         }
         Arc::new(DecoratedFunction {
             id_range: def.name.range,
             ty,
             metadata,
+            decorator_steps,
         })
     }
 
@@ -115,39 +115,30 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
         }
     }
 
-    fn tuple_concat(&self, l: &Tuple, r: &Tuple) -> Type {
+    /// Concatenate two tuples. A concrete result that is too long is widened, unless there is a
+    /// hint.
+    fn tuple_concat(&self, l: &Tuple, r: &Tuple, hint: Option<&Type>) -> Type {
         match (l, r) {
+            (Tuple::Concrete(l), Tuple::Concrete(r))
+                if self.should_widen_tuple(l.len() + r.len(), hint) =>
+            {
+                self.widened_tuple(&[l.as_slice(), r.as_slice()].concat())
+            }
             (Tuple::Concrete(l), Tuple::Concrete(r)) => {
                 let mut elements = l.clone();
                 elements.extend(r.clone());
-                Type::Tuple(Tuple::Concrete(elements))
+                Type::tuple(elements)
             }
             (Tuple::Unbounded(l), Tuple::Unbounded(r)) => Type::Tuple(Tuple::Unbounded(Box::new(
                 self.union((**l).clone(), (**r).clone()),
             ))),
-            (Tuple::Concrete(l), r @ Tuple::Unbounded(_)) => Type::Tuple(Tuple::Unpacked(
-                Box::new((l.clone(), Type::Tuple(r.clone()), Vec::new())),
-            )),
-            (l @ Tuple::Unbounded(_), Tuple::Concrete(r)) => Type::Tuple(Tuple::Unpacked(
-                Box::new((Vec::new(), Type::Tuple(l.clone()), r.clone())),
-            )),
-            (Tuple::Unpacked(box (l_prefix, l_middle, l_suffix)), Tuple::Concrete(r)) => {
-                let mut new_suffix = l_suffix.clone();
-                new_suffix.extend(r.clone());
-                Type::Tuple(Tuple::Unpacked(Box::new((
-                    l_prefix.clone(),
-                    l_middle.clone(),
-                    new_suffix,
-                ))))
-            }
-            (Tuple::Concrete(l), Tuple::Unpacked(box (r_prefix, r_middle, r_suffix))) => {
-                let mut new_prefix = l.clone();
-                new_prefix.extend(r_prefix.clone());
-                Type::Tuple(Tuple::Unpacked(Box::new((
-                    new_prefix,
-                    r_middle.clone(),
-                    r_suffix.clone(),
-                ))))
+            (Tuple::Concrete(l), r @ (Tuple::Unbounded(_) | Tuple::Unpacked(_))) => Type::Tuple(
+                l.iter()
+                    .rev()
+                    .fold(r.clone(), |acc, t| acc.prepend(t.clone())),
+            ),
+            (l @ (Tuple::Unbounded(_) | Tuple::Unpacked(_)), Tuple::Concrete(r)) => {
+                Type::Tuple(r.iter().fold(l.clone(), |acc, t| acc.append(t.clone())))
             }
             (Tuple::Unbounded(l), Tuple::Unpacked(box (r_prefix, r_middle, r_suffix))) => {
                 let mut middle = r_prefix.clone();
@@ -198,7 +189,49 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
         }
     }
 
-    pub fn binop_infer(&self, x: &ExprBinOp, errors: &ErrorCollector) -> Type {
+    /// Multiply a tuple by an int, in either order. A literal count on a concrete tuple gives the
+    /// repeated concrete tuple, and a literal count of zero or less gives `tuple[()]`. Otherwise,
+    /// we get a homogeneous tuple of the element types. Returns `None` if this isn't a tuple
+    /// multiplied by an int.
+    fn tuple_repeat(&self, lhs: &Type, rhs: &Type, hint: Option<&Type>) -> Option<Type> {
+        let (x, n) = match (lhs, rhs) {
+            (Type::Tuple(x), n) | (n, Type::Tuple(x)) => (x, n),
+            _ => return None,
+        };
+        let count = match n {
+            Type::Literal(Lit::Int(n)) => n.as_i64(),
+            Type::Literal(Lit::Bool(n)) => Some(*n as i64),
+            Type::ClassType(cls) if cls == self.stdlib.int() || cls == self.stdlib.bool() => None,
+            _ => return None,
+        };
+        // Even with a hint, only build a long tuple if it matches the hint, since the count can be
+        // arbitrarily large.
+        let repeat_precisely = |len: usize| {
+            !self.should_widen_tuple(len, None)
+                || matches!(hint, Some(Type::Tuple(Tuple::Concrete(want))) if want.len() == len)
+        };
+        let res = match (x, count) {
+            (_, Some(n)) if n <= 0 => Type::tuple(Vec::new()),
+            (Tuple::Concrete(elts), _) if elts.is_empty() => Type::tuple(Vec::new()),
+            (Tuple::Concrete(elts), Some(n))
+                if let Ok(n) = usize::try_from(n)
+                    && repeat_precisely(elts.len().saturating_mul(n)) =>
+            {
+                Type::tuple(elts.repeat(n))
+            }
+            (Tuple::Concrete(elts), _) => self.widened_tuple(elts),
+            (Tuple::Unbounded(_), _) => Type::Tuple(x.clone()),
+            (Tuple::Unpacked(box (prefix, middle, suffix)), _) => {
+                let mut elts = prefix.clone();
+                elts.extend(suffix.iter().cloned());
+                elts.push(self.unwrap_iterable(middle).unwrap_or(Type::any_implicit()));
+                Type::Tuple(Tuple::unbounded(self.unions(elts)))
+            }
+        };
+        Some(res)
+    }
+
+    pub fn binop_infer(&self, x: &ExprBinOp, hint: Option<&Type>, errors: &ErrorCollector) -> Type {
         let binop_call = |op: Operator, lhs: &Type, rhs: &Type, range: TextRange| -> Type {
             let context = || {
                 ErrorContext::BinaryOp(
@@ -236,7 +269,11 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                     && let Type::Tuple(l) = lhs
                     && let Type::Tuple(r) = rhs
                 {
-                    self.tuple_concat(l, r)
+                    self.tuple_concat(l, r, hint)
+                } else if x.op == Operator::Mult
+                    && let Some(res) = self.tuple_repeat(lhs, rhs, hint)
+                {
+                    res
                 } else {
                     binop_call(x.op, lhs, rhs, x.range)
                 }
@@ -269,6 +306,8 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
         let rhs = self.expr_infer(&x.value, errors);
         let tcc: &dyn Fn() -> TypeCheckContext =
             &|| TypeCheckContext::of_kind(TypeCheckKind::AugmentedAssignment);
+        let ann = ann.map(|k| self.get_idx(k));
+        let ann_ty = ann.as_ref().and_then(|ann| ann.ty(self.stdlib));
         let result = self.distribute_over_union(&base, |lhs| {
             self.distribute_over_union(&rhs, |rhs| {
                 if let Type::Any(style) = &base {
@@ -282,14 +321,18 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                     && let Type::Tuple(ref l) = base
                     && let Type::Tuple(r) = rhs
                 {
-                    self.tuple_concat(l, r)
+                    self.tuple_concat(l, r, ann_ty.as_ref())
+                } else if x.op == Operator::Mult
+                    && let Some(res) = self.tuple_repeat(lhs, rhs, ann_ty.as_ref())
+                {
+                    res
                 } else {
                     binop_call(x.op, lhs, rhs, x.range)
                 }
             })
         });
         // If we're assigning to something with an annotation, make sure the produced value is assignable to it
-        if let Some(ann) = ann.map(|k| self.get_idx(k)) {
+        if let Some(ann) = ann {
             if ann.annotation.is_final() {
                 self.error(
                     errors,
@@ -299,7 +342,7 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                     format!("Cannot assign to {} because it is marked final", ann.target),
                 );
             }
-            if let Some(ann_ty) = ann.ty(self.stdlib) {
+            if let Some(ann_ty) = ann_ty {
                 return self.check_and_return_type(&ann_ty, result, x.range(), errors, tcc);
             }
         }
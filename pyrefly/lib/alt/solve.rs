@@ -246,7 +246,8 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
         errors: &ErrorCollector,
     ) -> Arc<AnnotationWithTarget> {
         match binding {
-            BindingAnnotation::AnnotateExpr(target, x, class_key) => {
+            BindingAnnotation::AnnotateExpr(target, x, class_key)
+            | BindingAnnotation::ImplicitOptional(target, x, class_key) => {
                 let type_form_context = target.type_form_context();
                 let mut ann = self.expr_annotation(x, type_form_context, errors);
                 if let Some(class_key) = class_key
@@ -259,6 +260,12 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                         ));
                     }
                 }
+                if matches!(binding, BindingAnnotation::ImplicitOptional(..))
+                    && let Some(ty) = &mut ann.ty
+                    && !self.is_subset_eq(&Type::None, ty)
+                {
+                    *ty = self.union(ty.clone(), Type::None);
+                }
                 Arc::new(AnnotationWithTarget {
                     target: target.clone(),
                     annotation: ann,
@@ -554,6 +561,9 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                 vec![Iterable::FixedLen(elts.clone())]
             }
             Type::Tuple(Tuple::Concrete(elts)) => vec![Iterable::FixedLen(elts.clone())],
+            Type::Tuple(tuple) if let Some(elt) = tuple.element_type() => {
+                vec![Iterable::OfType(elt)]
+            }
             Type::Var(v) if let Some(_guard) = self.recurser.recurse(*v) => {
                 self.iterate(&self.solver().force_var(*v), range, errors)
             }
@@ -591,6 +601,54 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
         }
     }
 
+    /// The type of the value at `pos` when unpacking `tuple[*prefix, *middle, *suffix]`.
+    fn unpack_unpacked_tuple(
+        &self,
+        prefix: &[Type],
+        middle: &Type,
+        suffix: &[Type],
+        pos: &UnpackedPosition,
+    ) -> Type {
+        let middle = self
+            .unwrap_iterable(middle)
+            .unwrap_or_else(Type::any_implicit);
+        // Anything we can't pin down might be any of the elements.
+        let all = || {
+            self.unions(
+                prefix
+                    .iter()
+                    .cloned()
+                    .chain(iter::once(middle.clone()))
+                    .chain(suffix.iter().cloned())
+                    .collect(),
+            )
+        };
+        match *pos {
+            UnpackedPosition::Index(i) => prefix.get(i).cloned().unwrap_or_else(all),
+            UnpackedPosition::ReverseIndex(i) => suffix
+                .len()
+                .checked_sub(i)
+                .and_then(|i| suffix.get(i))
+                .cloned()
+                .unwrap_or_else(all),
+            UnpackedPosition::Slice(i, j) => {
+                let elem_ty = if i <= prefix.len() && j <= suffix.len() {
+                    self.unions(
+                        prefix[i..]
+                            .iter()
+                            .cloned()
+                            .chain(iter::once(middle.clone()))
+                            .chain(suffix[..suffix.len() - j].iter().cloned())
+                            .collect(),
+                    )
+                } else {
+                    all()
+                };
+                self.stdlib.list(elem_ty).to_type()
+            }
+        }
+    }
+
     /// Given a type, determine the async iteration type; this is the type
     /// of `x` if we were to loop using `async for x in iterable`.
     pub fn async_iterate(
@@ -944,7 +1002,7 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
         }
     }
 
-    fn context_value(
+    pub fn context_value(
         &self,
         context_manager_type: &Type,
         kind: IsAsync,
@@ -2084,7 +2142,7 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                             )
                         }
                     }
-                    Type::Any(AnyStyle::Error) => match_args,
+                    Type::Any(AnyStyle::Error | AnyStyle::Unresolved) => match_args,
                     _ => self.error(
                         errors,
                         *range,
@@ -2353,6 +2411,25 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                         let tcc: &dyn Fn() -> TypeCheckContext =
                             &|| TypeCheckContext::of_kind(TypeCheckKind::TypeGuardReturn);
                         self.expr(expr, hint.as_ref().map(|t| (t, tcc)), errors)
+                    } else if let Some(want @ Type::Tuple(Tuple::Concrete(want_elts))) = &hint
+                        && let Expr::Tuple(tuple) = &**expr
+                        && tuple.elts.iter().any(|x| matches!(x, Expr::Starred(_)))
+                    {
+                        // If splatting a variable-length tuple makes the returned tuple fail to
+                        // match a fixed-length annotation, explain that alongside the types.
+                        let got = self
+                            .expr_infer_type_info_with_hint(expr, Some(want), errors)
+                            .into_ty();
+                        let variable_length =
+                            matches!(&got, Type::Tuple(tuple) if !tuple.is_fixed_length());
+                        self.check_and_return_type(want, got, expr.range(), errors, &|| {
+                            TypeCheckContext {
+                                kind: TypeCheckKind::ExplicitFunctionReturn,
+                                context: variable_length.then(|| {
+                                    ErrorContext::VariableLengthTupleReturn(want_elts.len())
+                                }),
+                            }
+                        })
                     } else {
                         let tcc: &dyn Fn() -> TypeCheckContext =
                             &|| TypeCheckContext::of_kind(TypeCheckKind::ExplicitFunctionReturn);
@@ -2538,8 +2615,23 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                 }
             }
             Binding::UnpackedValue(ann, to_unpack, range, pos) => {
-                let iterables = self.iterate(self.get_idx(*to_unpack).ty(), *range, errors);
                 let mut values = Vec::new();
+                // Tuples with an unpacked middle know the exact types of their prefix and suffix,
+                // which we would lose by iterating over them.
+                let mut others = Vec::new();
+                for ty in self.get_idx(*to_unpack).ty().clone().into_unions() {
+                    match ty {
+                        Type::Tuple(Tuple::Unpacked(box (prefix, middle, suffix))) => {
+                            values.push(self.unpack_unpacked_tuple(&prefix, &middle, &suffix, pos))
+                        }
+                        ty => others.push(ty),
+                    }
+                }
+                let iterables = if others.is_empty() {
+                    Vec::new()
+                } else {
+                    self.iterate(&self.unions(others), *range, errors)
+                };
                 for iterable in iterables {
                     values.push(match iterable {
                         Iterable::OfType(ty) => match pos {
@@ -18,6 +18,7 @@ use ruff_python_ast::Keyword;
 use ruff_python_ast::name::Name;
 use ruff_text_size::Ranged;
 use ruff_text_size::TextRange;
+use vec1::vec1;
 
 use crate::alt::answers::AnswersSolver;
 use crate::alt::answers::LookupAnswer;
@@ -30,9 +31,39 @@ use crate::types::callable::FunctionKind;
 use crate::types::callable::unexpected_keyword;
 use crate::types::class::Class;
 use crate::types::special_form::SpecialForm;
+use crate::types::tuple::Tuple;
 use crate::types::types::Type;
 
 impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
+    /// When `assert_type` fails on two concrete tuples, point at the first element that differs,
+    /// or at the lengths if those differ. Unpacked tuples don't have an obvious element alignment,
+    /// so we only show the whole types for those.
+    fn explain_tuple_mismatch(&self, got: &Type, want: &Type) -> Option<String> {
+        match (got, want) {
+            (Type::Tuple(Tuple::Concrete(got)), Type::Tuple(Tuple::Concrete(want))) => {
+                if got.len() != want.len() {
+                    Some(format!(
+                        "tuple lengths differ: expected {}, got {}",
+                        want.len(),
+                        got.len()
+                    ))
+                } else {
+                    let (i, (got, want)) = got
+                        .iter()
+                        .zip(want)
+                        .enumerate()
+                        .find(|(_, (x, y))| x != y)?;
+                    Some(format!(
+                        "element {i}: expected {}, got {}",
+                        self.for_display(want.clone()),
+                        self.for_display(got.clone())
+                    ))
+                }
+            }
+            _ => None,
+        }
+    }
+
     pub fn call_assert_type(
         &self,
         args: &[Expr],
@@ -59,18 +90,15 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
             let self_form = Type::SpecialForm(SpecialForm::SelfType);
             a.subst_self_type_mut(&self_form, &|_, _| true);
             b.subst_self_type_mut(&self_form, &|_, _| true);
-            if a != b {
-                self.error(
-                    errors,
-                    range,
-                    ErrorKind::AssertType,
-                    None,
-                    format!(
-                        "assert_type({}, {}) failed",
-                        self.for_display(a),
-                        self.for_display(b)
-                    ),
-                );
+            if a.clone().canonicalize_unbounded_runs() != b.clone().canonicalize_unbounded_runs() {
+                let detail = self.explain_tuple_mismatch(&a, &b);
+                let mut msg = vec1![format!(
+                    "assert_type({}, {}) failed",
+                    self.for_display(a),
+                    self.for_display(b)
+                )];
+                msg.extend(detail);
+                errors.add(range, ErrorKind::AssertType, None, msg);
             }
         } else {
             self.error(
@@ -30,6 +30,7 @@ use crate::types::lit_int::LitInt;
 use crate::types::literal::Lit;
 use crate::types::special_form::SpecialForm;
 use crate::types::tuple::Tuple;
+use crate::types::tuple::TupleSpecError;
 use crate::types::types::AnyStyle;
 use crate::types::types::Type;
 
@@ -48,7 +49,7 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
             range,
             ErrorKind::BadUnpacking,
             None,
-            "Only one unbounded type is allowed to be unpacked".to_owned(),
+            TupleSpecError::ExtraUnpack.to_string(),
         )
     }
 
@@ -76,8 +77,7 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                             value.range(),
                             ErrorKind::InvalidArgument,
                             None,
-                            "`...` cannot be used with an unpacked `TypeVarTuple` or tuple"
-                                .to_owned(),
+                            TupleSpecError::EllipsisWithUnpack.to_string(),
                         );
                         return None;
                     } else {
@@ -89,7 +89,7 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                         value.range(),
                         ErrorKind::InvalidArgument,
                         None,
-                        "Invalid position for `...`".to_owned(),
+                        TupleSpecError::MisplacedEllipsis.to_string(),
                     );
                     return None;
                 }
@@ -139,7 +139,7 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                         value.range(),
                         ErrorKind::BadUnpacking,
                         None,
-                        format!("Expected a tuple or `TypeVarTuple`, got `{}`", ty),
+                        TupleSpecError::NotUnpackable(*ty).to_string(),
                     );
                     return None;
                 }
@@ -149,7 +149,7 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                         value.range(),
                         ErrorKind::InvalidTypeVarTuple,
                         None,
-                        "`TypeVarTuple` must be unpacked".to_owned(),
+                        TupleSpecError::TypeVarTupleNotUnpacked.to_string(),
                     );
                     return None;
                 }
@@ -203,7 +203,9 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
             Expr::Name(_) => {
                 fn is_valid_literal(x: &Type) -> bool {
                     match x {
-                        Type::None | Type::Literal(_) | Type::Any(AnyStyle::Error) => true,
+                        Type::None
+                        | Type::Literal(_)
+                        | Type::Any(AnyStyle::Error | AnyStyle::Unresolved) => true,
                         Type::Union(xs) => xs.iter().all(is_valid_literal),
                         _ => false,
                     }
@@ -235,7 +237,9 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                     {
                         literals.push(e.to_type())
                     }
-                    Type::Any(AnyStyle::Error) => literals.push(Type::any_error()),
+                    Type::Any(AnyStyle::Error | AnyStyle::Unresolved) => {
+                        literals.push(Type::any_error())
+                    }
                     _ => {
                         self.error(
                             errors,
@@ -256,7 +260,9 @@ impl<'a, Ans: LookupAnswer> AnswersSolver<'a, Ans> {
                 let ty = self.expr_infer(x, errors);
                 self.map_over_union(&ty, |ty| match ty {
                     Type::Type(box lit @ Type::Literal(_)) => literals.push(lit.clone()),
-                    Type::Any(AnyStyle::Error) => literals.push(Type::any_error()),
+                    Type::Any(AnyStyle::Error | AnyStyle::Unresolved) => {
+                        literals.push(Type::any_error())
+                    }
                     _ => {
                         self.error(
                             errors,
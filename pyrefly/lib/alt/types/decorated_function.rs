@@ -30,6 +30,10 @@ pub struct DecoratedFunction {
     pub id_range: TextRange,
     pub ty: Type,
     pub metadata: FuncMetadata,
+    /// The type before any decorators are applied, followed by the type after each decorator,
+    /// innermost first. Empty if no decorators are applied. Decorators that only contribute
+    /// metadata, such as `@property` or `@staticmethod`, are not included.
+    pub decorator_steps: Vec<Type>,
 }
 
 impl Display for DecoratedFunction {
@@ -51,6 +55,7 @@ impl DecoratedFunction {
                 })),
                 flags: FuncFlags::default(),
             },
+            decorator_steps: Vec::new(),
         }
     }
 }
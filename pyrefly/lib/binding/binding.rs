@@ -1519,6 +1519,9 @@ pub enum BindingAnnotation {
     /// The type is annotated to be this key, will have the outer type removed.
     /// Optionally occurring within a class, in which case Self refers to this class.
     AnnotateExpr(AnnotationTarget, Expr, Option<Idx<KeyClass>>),
+    /// Like `AnnotateExpr`, but for a parameter with a `None` default under
+    /// `implicit-optional = "widen"`, so `None` is added to the type if it doesn't already allow it.
+    ImplicitOptional(AnnotationTarget, Expr, Option<Idx<KeyClass>>),
     /// A literal type we know statically.
     Type(AnnotationTarget, Type),
 }
@@ -1535,6 +1538,15 @@ impl DisplayWith<Bindings> for BindingAnnotation {
                     Some(t) => ctx.display(*t).to_string(),
                 }
             ),
+            Self::ImplicitOptional(target, x, class_key) => write!(
+                f,
+                "ImplicitOptional({target}, {}, {})",
+                ctx.module_info().display(x),
+                match class_key {
+                    None => "None".to_owned(),
+                    Some(t) => ctx.display(*t).to_string(),
+                }
+            ),
             Self::Type(target, t) => write!(f, "Type({target}, {t})"),
         }
     }
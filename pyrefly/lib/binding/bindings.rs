@@ -62,6 +62,7 @@ use crate::binding::scope::ScopeTrace;
 use crate::binding::scope::Scopes;
 use crate::binding::table::TableKeyed;
 use crate::common::symbol_kind::SymbolKind;
+use crate::config::base::ImplicitOptional;
 use crate::config::base::UntypedDefBehavior;
 use crate::error::collector::ErrorCollector;
 use crate::error::context::ErrorContext;
@@ -136,6 +137,7 @@ pub struct BindingsBuilder<'a> {
     pub scopes: Scopes,
     table: BindingTable,
     pub untyped_def_behavior: UntypedDefBehavior,
+    pub implicit_optional: ImplicitOptional,
 }
 
 impl Bindings {
@@ -179,6 +181,15 @@ impl Bindings {
         self.0.table.get::<Key>().0.key_to_idx(k).is_some()
     }
 
+    /// Like `key_to_idx`, but returns `None` for a key that doesn't exist, rather than panicking.
+    /// See `is_valid_key` for when that can happen.
+    pub fn try_key_to_idx<K: Keyed>(&self, k: &K) -> Option<Idx<K>>
+    where
+        BindingTable: TableKeyed<K, Value = BindingEntry<K>>,
+    {
+        self.0.table.get::<K>().0.key_to_idx(k)
+    }
+
     pub fn key_to_idx<K: Keyed>(&self, k: &K) -> Idx<K>
     where
         BindingTable: TableKeyed<K, Value = BindingEntry<K>>,
@@ -279,6 +290,7 @@ impl Bindings {
         uniques: &UniqueFactory,
         enable_trace: bool,
         untyped_def_behavior: UntypedDefBehavior,
+        implicit_optional: ImplicitOptional,
     ) -> Self {
         let mut builder = BindingsBuilder {
             module_info: module_info.dupe(),
@@ -292,6 +304,7 @@ impl Bindings {
             scopes: Scopes::module(x.range, enable_trace),
             table: Default::default(),
             untyped_def_behavior,
+            implicit_optional,
         };
         builder.init_static_scope(&x.body, true);
         if module_info.name() != ModuleName::builtins() {
@@ -1151,10 +1164,21 @@ impl<'a> BindingsBuilder<'a> {
         class_key: Option<Idx<KeyClass>>,
     ) {
         let name = x.name();
+        let implicit_optional = self.implicit_optional == ImplicitOptional::Widen
+            && match x {
+                AnyParameterRef::NonVariadic(x) => {
+                    matches!(x.default.as_deref(), Some(Expr::NoneLiteral(_)))
+                }
+                AnyParameterRef::Variadic(_) => false,
+            };
         let annot = x.annotation().map(|x| {
             self.insert_binding(
                 KeyAnnotation::Annotation(ShortIdentifier::new(name)),
-                BindingAnnotation::AnnotateExpr(target.clone(), x.clone(), class_key),
+                if implicit_optional {
+                    BindingAnnotation::ImplicitOptional(target.clone(), x.clone(), class_key)
+                } else {
+                    BindingAnnotation::AnnotateExpr(target.clone(), x.clone(), class_key)
+                },
             )
         });
         let (annot, def) = match annot {
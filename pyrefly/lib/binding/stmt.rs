@@ -48,12 +48,12 @@ use crate::types::special_form::SpecialForm;
 use crate::types::types::Type;
 
 impl<'a> BindingsBuilder<'a> {
-    fn bind_unimportable_names(&mut self, x: &StmtImportFrom) {
+    fn bind_unimportable_names(&mut self, x: &StmtImportFrom, ty: Type) {
         for x in &x.names {
             if &x.name != "*" {
                 let asname = x.asname.as_ref().unwrap_or(&x.name);
                 // We pass None as imported_from, since we are really faking up a local error definition
-                self.bind_definition(asname, Binding::Type(Type::any_error()), FlowStyle::Other);
+                self.bind_definition(asname, Binding::Type(ty.clone()), FlowStyle::Other);
             }
         }
     }
@@ -893,7 +893,9 @@ impl<'a> BindingsBuilder<'a> {
                                 }
                             }
                         }
-                        Err(FindError::Ignored) => self.bind_unimportable_names(&x),
+                        Err(FindError::Ignored) => {
+                            self.bind_unimportable_names(&x, Type::any_error())
+                        }
                         Err(
                             err @ (FindError::NoPyTyped
                             | FindError::NoSource(_)
@@ -906,7 +908,7 @@ impl<'a> BindingsBuilder<'a> {
                                 ctx.as_deref(),
                                 msg,
                             );
-                            self.bind_unimportable_names(&x);
+                            self.bind_unimportable_names(&x, Type::any_unresolved());
                         }
                     }
                 } else {
@@ -919,7 +921,7 @@ impl<'a> BindingsBuilder<'a> {
                             ".".repeat(x.level as usize)
                         ),
                     );
-                    self.bind_unimportable_names(&x);
+                    self.bind_unimportable_names(&x, Type::any_unresolved());
                 }
             }
             Stmt::Global(x) => {
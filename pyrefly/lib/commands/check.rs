@@ -42,6 +42,7 @@ use tracing::debug;
 use crate::commands::run::CommandExitStatus;
 use crate::commands::suppress;
 use crate::commands::util::module_from_path;
+use crate::config::base::ImplicitOptional;
 use crate::config::base::UntypedDefBehavior;
 use crate::config::config::ConfigFile;
 use crate::config::config::validate_path;
@@ -200,6 +201,12 @@ struct ConfigOverrideArgs {
     /// Controls how Pyrefly analyzes function definitions that lack type annotations on parameters and return values.
     #[arg(long, env = clap_env("UNTYPED_DEF_BEHAVIOR"))]
     untyped_def_behavior: Option<UntypedDefBehavior>,
+    /// Controls whether a `None` default on a parameter whose annotation doesn't allow `None` is an error, or widens the annotation.
+    #[arg(long, env = clap_env("IMPLICIT_OPTIONAL"))]
+    implicit_optional: Option<ImplicitOptional>,
+    /// Inferred fixed-length tuples with more elements than this are widened to `tuple[T, ...]`.
+    #[arg(long, env = clap_env("MAX_CONCRETE_TUPLE_LENGTH"))]
+    max_concrete_tuple_length: Option<usize>,
 }
 
 impl OutputFormat {
@@ -587,6 +594,12 @@ impl Args {
         if let Some(x) = &self.config_override.untyped_def_behavior {
             config.root.untyped_def_behavior = Some(*x);
         }
+        if let Some(x) = &self.config_override.implicit_optional {
+            config.root.implicit_optional = Some(*x);
+        }
+        if let Some(x) = &self.config_override.max_concrete_tuple_length {
+            config.root.max_concrete_tuple_length = Some(*x);
+        }
         if let Some(wildcards) = &self.config_override.replace_imports_with_any {
             config.root.replace_imports_with_any = Some(
                 wildcards
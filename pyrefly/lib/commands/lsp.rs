@@ -188,6 +188,7 @@ use crate::state::state::CommittingTransaction;
 use crate::state::state::State;
 use crate::state::state::Transaction;
 use crate::state::state::TransactionData;
+use crate::types::types::UNRESOLVED_IMPORT_NOTE;
 
 #[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq, Default)]
 pub(crate) enum IndexingMode {
@@ -1780,6 +1781,11 @@ impl Server {
             .lined_buffer()
             .from_lsp_position(params.text_document_position_params.position);
         let t = transaction.get_type_at(&handle, range)?;
+        let degraded_formatted = if t.is_degraded_by_unresolved_import() {
+            format!("\n\n{UNRESOLVED_IMPORT_NOTE}")
+        } else {
+            "".to_owned()
+        };
         let mut kind_formatted: String = "".to_owned();
         let mut docstring_formatted: String = "".to_owned();
         if let Some((definition_metadata, text_range_with_module_info, docstring)) =
@@ -1802,8 +1808,8 @@ impl Server {
             contents: HoverContents::Markup(MarkupContent {
                 kind: MarkupKind::Markdown,
                 value: format!(
-                    "```python\n{}{}\n```{}",
-                    kind_formatted, t, docstring_formatted
+                    "```python\n{}{}\n```{}{}",
+                    kind_formatted, t, degraded_formatted, docstring_formatted
                 ),
             }),
             range: None,
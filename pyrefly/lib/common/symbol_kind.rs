@@ -26,6 +26,8 @@ pub enum SymbolKind {
     TypeParameter,
     TypeAlias,
     Function,
+    Method,
+    Property,
     Class,
 }
 
@@ -40,6 +42,8 @@ impl SymbolKind {
             SymbolKind::TypeParameter => CompletionItemKind::TYPE_PARAMETER,
             SymbolKind::TypeAlias => CompletionItemKind::INTERFACE,
             SymbolKind::Function => CompletionItemKind::FUNCTION,
+            SymbolKind::Method => CompletionItemKind::METHOD,
+            SymbolKind::Property => CompletionItemKind::PROPERTY,
             SymbolKind::Class => CompletionItemKind::CLASS,
         }
     }
@@ -54,6 +58,8 @@ impl SymbolKind {
             SymbolKind::TypeParameter => "(type parameter)".to_owned(),
             SymbolKind::TypeAlias => "(type alias)".to_owned(),
             SymbolKind::Function => "(function)".to_owned(),
+            SymbolKind::Method => "(method)".to_owned(),
+            SymbolKind::Property => "(property)".to_owned(),
             SymbolKind::Class => "(class)".to_owned(),
         }
     }
@@ -74,6 +80,8 @@ impl SymbolKind {
             SymbolKind::TypeAlias => (SemanticTokenType::INTERFACE, vec![]),
             // todo(samzhou19815): modifier for async
             SymbolKind::Function => (SemanticTokenType::FUNCTION, vec![]),
+            SymbolKind::Method => (SemanticTokenType::METHOD, vec![]),
+            SymbolKind::Property => (SemanticTokenType::PROPERTY, vec![]),
             SymbolKind::Class => (SemanticTokenType::CLASS, vec![]),
         }
     }
@@ -23,6 +23,22 @@ pub enum UntypedDefBehavior {
     SkipAndInferReturnAny,
 }
 
+/// How to treat a parameter with a `None` default whose annotation doesn't allow `None`,
+/// e.g. `def f(x: int = None)`.
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, Clone, Copy, Default)]
+#[derive(ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImplicitOptional {
+    /// Report the default as not assignable to the annotation, as the typing spec requires.
+    #[default]
+    Error,
+    /// Silently widen the annotation to also allow `None`, as older type checkers did.
+    Widen,
+}
+
+/// The default for `max-concrete-tuple-length`.
+pub const DEFAULT_MAX_CONCRETE_TUPLE_LENGTH: usize = 256;
+
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize, Clone, Default)]
 #[serde(rename_all = "kebab-case")]
 pub struct ConfigBase {
@@ -51,6 +67,15 @@ pub struct ConfigBase {
     )]
     pub untyped_def_behavior: Option<UntypedDefBehavior>,
 
+    /// How should we handle a `None` default on a parameter annotated with a type that doesn't allow `None`?
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub implicit_optional: Option<ImplicitOptional>,
+
+    /// Inferred fixed-length tuples with more elements than this are widened to `tuple[T, ...]`,
+    /// since huge tuple types are expensive to build and check against.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concrete_tuple_length: Option<usize>,
+
     /// Whether to ignore type errors in generated code. By default this is disabled.
     /// Generated code is defined as code that contains the marker string `@` immediately followed by `generated`.
     #[serde(
@@ -80,6 +105,14 @@ impl ConfigBase {
         base.untyped_def_behavior
     }
 
+    pub fn get_implicit_optional(base: &Self) -> Option<ImplicitOptional> {
+        base.implicit_optional
+    }
+
+    pub fn get_max_concrete_tuple_length(base: &Self) -> Option<usize> {
+        base.max_concrete_tuple_length
+    }
+
     pub fn get_ignore_errors_in_generated_code(base: &Self) -> Option<bool> {
         base.ignore_errors_in_generated_code
     }
@@ -27,6 +27,8 @@ use tracing::debug;
 use tracing::warn;
 
 use crate::config::base::ConfigBase;
+use crate::config::base::DEFAULT_MAX_CONCRETE_TUPLE_LENGTH;
+use crate::config::base::ImplicitOptional;
 use crate::config::base::UntypedDefBehavior;
 use crate::config::environment::conda;
 use crate::config::environment::environment::PythonEnvironment;
@@ -489,6 +491,22 @@ impl ConfigFile {
                 self.root.untyped_def_behavior.unwrap())
     }
 
+    pub fn implicit_optional(&self, path: &Path) -> ImplicitOptional {
+        self.get_from_sub_configs(ConfigBase::get_implicit_optional, path)
+            .unwrap_or_else(||
+                // we can use unwrap here, because the value in the root config must
+                // be set in `ConfigFile::configure()`.
+                self.root.implicit_optional.unwrap())
+    }
+
+    pub fn max_concrete_tuple_length(&self, path: &Path) -> usize {
+        self.get_from_sub_configs(ConfigBase::get_max_concrete_tuple_length, path)
+            .unwrap_or_else(||
+                // we can use unwrap here, because the value in the root config must
+                // be set in `ConfigFile::configure()`.
+                self.root.max_concrete_tuple_length.unwrap())
+    }
+
     fn ignore_errors_in_generated_code(&self, path: &Path) -> bool {
         self.get_from_sub_configs(ConfigBase::get_ignore_errors_in_generated_code, path)
             .unwrap_or_else(||
@@ -566,6 +584,14 @@ impl ConfigFile {
             self.root.untyped_def_behavior = Some(Default::default());
         }
 
+        if self.root.implicit_optional.is_none() {
+            self.root.implicit_optional = Some(Default::default());
+        }
+
+        if self.root.max_concrete_tuple_length.is_none() {
+            self.root.max_concrete_tuple_length = Some(DEFAULT_MAX_CONCRETE_TUPLE_LENGTH);
+        }
+
         if self.root.ignore_errors_in_generated_code.is_none() {
             self.root.ignore_errors_in_generated_code = Some(Default::default());
         }
@@ -786,6 +812,7 @@ mod tests {
             project-includes = ["tests", "./implementation"]
             project-excludes = ["tests/untyped/**"]
             untyped-def-behavior = "check-and-infer-return-type"
+            implicit-optional = "widen"
             search-path = ["../.."]
             python-platform = "darwin"
             python-version = "1.2.3"
@@ -843,6 +870,8 @@ mod tests {
                     ignore_errors_in_generated_code: Some(true),
                     replace_imports_with_any: Some(vec![ModuleWildcard::new("fibonacci").unwrap()]),
                     untyped_def_behavior: Some(UntypedDefBehavior::CheckAndInferReturnType),
+                    implicit_optional: Some(ImplicitOptional::Widen),
+                    max_concrete_tuple_length: None,
                 },
                 custom_module_paths: Default::default(),
                 sub_configs: vec![SubConfig {
@@ -856,6 +885,8 @@ mod tests {
                         ignore_errors_in_generated_code: Some(false),
                         replace_imports_with_any: Some(Vec::new()),
                         untyped_def_behavior: Some(UntypedDefBehavior::CheckAndInferReturnAny),
+                        implicit_optional: None,
+                        max_concrete_tuple_length: None,
                     }
                 }],
                 use_untyped_imports: true,
@@ -1200,6 +1231,8 @@ mod tests {
                 errors: Some(Default::default()),
                 replace_imports_with_any: Some(vec![ModuleWildcard::new("root").unwrap()]),
                 untyped_def_behavior: Some(UntypedDefBehavior::CheckAndInferReturnType),
+                implicit_optional: Some(ImplicitOptional::Error),
+                max_concrete_tuple_length: None,
                 ignore_errors_in_generated_code: Some(false),
                 extras: Default::default(),
             },
@@ -39,6 +39,8 @@ pub enum ErrorContext {
     Await(Type),
     /// x[y]
     Index(Type),
+    /// x[y], where x is a tuple and so y must be an integer or slice
+    TupleIndex(Type),
     /// x[y] = ...
     SetItem(Type),
     /// del x[y]
@@ -46,6 +48,8 @@ pub enum ErrorContext {
     /// match x: case Foo(y): ...
     MatchPositional(Type),
     ImportNotFound(ModuleName),
+    /// return 1, *x, where x is a variable-length tuple and the return annotation has a fixed length
+    VariableLengthTupleReturn(usize),
 }
 
 /// The context in which a got <: want type check occurs. This differs from ErrorContext in that
@@ -43,6 +43,9 @@ impl ErrorContext {
             Self::AsyncIteration(ty) => format!("Type `{ty}` is not an async iterable"),
             Self::Await(ty) => format!("Type `{ty}` is not awaitable"),
             Self::Index(ty) => format!("Cannot index into `{ty}`"),
+            Self::TupleIndex(ty) => {
+                format!("Cannot index into `{ty}`, expected an integer or slice")
+            }
             Self::SetItem(ty) => format!("Cannot set item in `{ty}`"),
             Self::DelItem(ty) => format!("Cannot delete item in `{ty}`"),
             Self::MatchPositional(ty) => {
@@ -51,6 +54,9 @@ impl ErrorContext {
             Self::ImportNotFound(import) => {
                 format!("Could not find import of `{import}`")
             }
+            Self::VariableLengthTupleReturn(len) => format!(
+                "A variable-length tuple cannot be returned where a tuple of length {len} is expected"
+            ),
         }
     }
 }
@@ -73,6 +73,8 @@ pub mod library {
                 pub use crate::config::finder;
                 pub use crate::error::kind::Severity;
                 pub use crate::module::module_name::ModuleName;
+                pub use crate::module::module_name::ModuleNameMap;
+                pub use crate::module::module_name::SortedModuleNameMap;
                 pub use crate::module::module_path::ModulePath;
                 pub use crate::python::sys_info::PythonPlatform;
                 pub use crate::python::sys_info::PythonVersion;
@@ -92,6 +92,42 @@ impl Ignore {
         None
     }
 
+    /// The error codes listed in brackets in a suppression comment,
+    /// e.g. `["bad-return"]` for `# type: ignore[bad-return]`.
+    pub fn get_suppression_codes(line: &str) -> Vec<String> {
+        for l in line.split("# ").skip(1) {
+            let Some(rest) = l
+                .strip_prefix("type: ignore")
+                .or_else(|| l.strip_prefix("pyrefly: ignore"))
+            else {
+                continue;
+            };
+            if let Some(rest) = rest.strip_prefix('[')
+                && let Some((codes, _)) = rest.split_once(']')
+            {
+                return codes
+                    .split(',')
+                    .map(|x| x.trim())
+                    .filter(|x| !x.is_empty())
+                    .map(|x| x.to_owned())
+                    .collect();
+            }
+            return Vec::new();
+        }
+        Vec::new()
+    }
+
+    /// All the lines with a suppression comment on them.
+    pub fn lines(&self) -> impl Iterator<Item = LineNumber> + '_ {
+        self.ignores.keys().copied()
+    }
+
+    /// Would a suppression comment on `line` suppress an error spanning `start_line` to `end_line`.
+    pub fn covers(line: LineNumber, start_line: LineNumber, end_line: LineNumber) -> bool {
+        (start_line.to_zero_indexed().saturating_sub(1)..=end_line.to_zero_indexed())
+            .contains(&line.to_zero_indexed())
+    }
+
     pub fn is_ignored(&self, start_line: LineNumber, end_line: LineNumber) -> bool {
         if self.ignore_all {
             true
@@ -126,6 +162,20 @@ mod tests {
         assert!(Ignore::get_suppression_kind("normal line").is_none());
     }
 
+    #[test]
+    fn test_get_suppression_codes() {
+        assert_eq!(
+            Ignore::get_suppression_codes("x # type: ignore[bad-return, bad-assignment]"),
+            vec!["bad-return", "bad-assignment"]
+        );
+        assert_eq!(
+            Ignore::get_suppression_codes("# pyrefly: ignore[bad-return]"),
+            vec!["bad-return"]
+        );
+        assert!(Ignore::get_suppression_codes("x # type: ignore").is_empty());
+        assert!(Ignore::get_suppression_codes("normal line").is_empty());
+    }
+
     #[test]
     fn test_has_ignore_all() {
         assert!(Ignore::has_ignore_all(
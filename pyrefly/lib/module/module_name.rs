@@ -5,11 +5,14 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::ffi::OsString;
 use std::fmt;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::hash::Hash;
+use std::iter;
 use std::path::Path;
 
 use dupe::Dupe;
@@ -21,6 +24,7 @@ use serde::Deserialize;
 use serde::Deserializer;
 use serde::Serialize;
 use serde::Serializer;
+use starlark_map::small_map::SmallMap;
 use static_interner::Intern;
 use static_interner::Interner;
 use thiserror::Error;
@@ -30,9 +34,27 @@ use crate::python::dunder;
 static MODULE_NAME_INTERNER: Interner<String> = Interner::new();
 
 /// The name of a python module. Examples: `foo.bar.baz`, `.foo.bar`.
-#[derive(Clone, Dupe, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+/// Module names are ordered lexicographically by their text.
+///
+/// Names are interned, so equality and hashing are by identity, which agrees with comparing the
+/// text within a single process. Hash values are not stable between processes, so anything that
+/// must come out the same across runs shouldn't depend on them, e.g. by iterating a `HashMap`.
+/// Use `ModuleNameMap` or `SortedModuleNameMap` instead.
+#[derive(Clone, Dupe, Copy, Hash, PartialEq, Eq)]
 pub struct ModuleName(Intern<String>);
 
+impl PartialOrd for ModuleName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ModuleName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
 impl<To: 'static> Visit<To> for ModuleName {
     const RECURSE_CONTAINS: bool = false;
     fn recurse<'a>(&'a self, _: &mut dyn FnMut(&'a To)) {}
@@ -58,11 +80,134 @@ impl<'de> Deserialize<'de> for ModuleName {
 
 impl Display for ModuleName {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.0.is_empty() {
-            write!(f, ".")
-        } else {
-            write!(f, "{}", self.0)
-        }
+        f.write_str(self.as_display_str())
+    }
+}
+
+impl PartialEq<str> for ModuleName {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for ModuleName {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// A `ModuleName` that serializes as `{"module": "a.b.c"}` rather than a bare string,
+/// for formats that need to tell module names apart from other strings.
+#[derive(Debug, Clone, Dupe, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize)]
+pub struct TaggedModuleName {
+    pub module: ModuleName,
+}
+
+impl From<ModuleName> for TaggedModuleName {
+    fn from(module: ModuleName) -> Self {
+        Self { module }
+    }
+}
+
+impl From<TaggedModuleName> for ModuleName {
+    fn from(tagged: TaggedModuleName) -> Self {
+        tagged.module
+    }
+}
+
+/// A map keyed by `ModuleName` that iterates in insertion order. The order never depends on how
+/// names hash, so it is the same in every process that performs the same insertions.
+#[derive(Debug, Clone)]
+pub struct ModuleNameMap<V>(SmallMap<ModuleName, V>);
+
+impl<V> Default for ModuleNameMap<V> {
+    fn default() -> Self {
+        Self(SmallMap::new())
+    }
+}
+
+impl<V> ModuleNameMap<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a value, returning the old one. Replacing a value keeps the name's position.
+    pub fn insert(&mut self, name: ModuleName, value: V) -> Option<V> {
+        self.0.insert(name, value)
+    }
+
+    pub fn get(&self, name: ModuleName) -> Option<&V> {
+        self.0.get(&name)
+    }
+
+    pub fn contains_key(&self, name: ModuleName) -> bool {
+        self.0.contains_key(&name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (ModuleName, &V)> {
+        self.0.iter().map(|(k, v)| (*k, v))
+    }
+}
+
+impl<V> FromIterator<(ModuleName, V)> for ModuleNameMap<V> {
+    fn from_iter<T: IntoIterator<Item = (ModuleName, V)>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// A map keyed by `ModuleName` that iterates in lexicographic order of the names, regardless of
+/// the order they were inserted in.
+#[derive(Debug, Clone)]
+pub struct SortedModuleNameMap<V>(BTreeMap<ModuleName, V>);
+
+impl<V> Default for SortedModuleNameMap<V> {
+    fn default() -> Self {
+        Self(BTreeMap::new())
+    }
+}
+
+impl<V> SortedModuleNameMap<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: ModuleName, value: V) -> Option<V> {
+        self.0.insert(name, value)
+    }
+
+    pub fn get(&self, name: ModuleName) -> Option<&V> {
+        self.0.get(&name)
+    }
+
+    pub fn contains_key(&self, name: ModuleName) -> bool {
+        self.0.contains_key(&name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (ModuleName, &V)> {
+        self.0.iter().map(|(k, v)| (*k, v))
+    }
+}
+
+impl<V> FromIterator<(ModuleName, V)> for SortedModuleNameMap<V> {
+    fn from_iter<T: IntoIterator<Item = (ModuleName, V)>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
     }
 }
 
@@ -156,6 +301,16 @@ impl ModuleName {
         ModuleName(MODULE_NAME_INTERNER.intern(x))
     }
 
+    /// Intern a batch of names, e.g. when loading a large project. Names that repeat within the
+    /// batch only go to the shared interner once.
+    pub fn intern_many<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<ModuleName> {
+        let mut interned = SmallMap::new();
+        names
+            .into_iter()
+            .map(|x| *interned.entry(x).or_insert_with(|| Self::from_str(x)))
+            .collect()
+    }
+
     pub fn from_name(x: &Name) -> Self {
         Self::from_str(x)
     }
@@ -164,11 +319,32 @@ impl ModuleName {
         Self::from_string(itertools::join(parts, "."))
     }
 
+    /// Join the names with `.`. An empty slice gives the empty name, displayed as `.`.
+    pub fn from_names(names: &[Name]) -> Self {
+        match names {
+            [] => Self::from_str(""),
+            [name] => Self::from_name(name),
+            _ => {
+                let len = names.iter().map(|x| x.len()).sum::<usize>() + names.len() - 1;
+                let mut res = String::with_capacity(len);
+                for (i, name) in names.iter().enumerate() {
+                    if i > 0 {
+                        res.push('.');
+                    }
+                    res.push_str(name);
+                }
+                Self::from_string(res)
+            }
+        }
+    }
+
     pub fn from_relative_path(path: &Path) -> anyhow::Result<Self> {
         let mut components = Vec::new();
         for raw_component in path.components() {
             if let Some(component) = raw_component.as_os_str().to_str() {
-                components.push(component)
+                // `Path` only treats `\` as a separator on Windows, but it is never valid
+                // in a module name, so split on it everywhere to get the same answer.
+                components.extend(component.split('\\'))
             } else {
                 return Err(anyhow::anyhow!(PathConversionError::ComponentNotUTF8 {
                     component: raw_component.as_os_str().to_owned(),
@@ -193,6 +369,25 @@ impl ModuleName {
         Ok(ModuleName::from_parts(components))
     }
 
+    /// Convert `path`, which must be under `root`, to a module name, treating a top-level
+    /// directory `foo-stubs` as a PEP 561 stub package for `foo`. For example,
+    /// `root/foo-stubs/bar.pyi` is `foo.bar`. Since `-` can't appear in an importable name,
+    /// a top-level directory ending in `-stubs` is always taken to be a stub package; a
+    /// top-level file such as `foo-stubs.pyi` is left alone.
+    pub fn from_stub_package_path(path: &Path, root: &Path) -> anyhow::Result<Self> {
+        let relative = path.strip_prefix(root)?;
+        let name = Self::from_relative_path(relative)?;
+        let is_directory = relative.components().nth(1).is_some();
+        match name.first_component_str().strip_suffix("-stubs") {
+            Some(package) if is_directory && !package.is_empty() => {
+                let mut parts = name.components();
+                parts[0] = Name::new(package);
+                Ok(Self::from_names(&parts))
+            }
+            _ => Ok(name),
+        }
+    }
+
     pub fn append(self, name: &Name) -> Self {
         Self::from_string(format!("{}.{}", self.as_str(), name))
     }
@@ -232,20 +427,82 @@ impl ModuleName {
         &self.0
     }
 
+    /// The same string that `Display` would produce, i.e. `.` for the empty name,
+    /// but without going through a formatter.
+    pub fn as_display_str(&self) -> &str {
+        if self.0.is_empty() {
+            "."
+        } else {
+            self.as_str()
+        }
+    }
+
+    /// Whether this module name is spelled exactly `s`. Unlike comparing against
+    /// `ModuleName::from_str(s)`, this doesn't intern `s`.
+    pub fn content_eq(self, s: &str) -> bool {
+        self == s
+    }
+
     pub fn first_component(self) -> Name {
+        Name::new(self.first_component_str())
+    }
+
+    /// Like `first_component`, but borrows rather than allocating a `Name`.
+    pub fn first_component_str(&self) -> &str {
         match self.0.split_once('.') {
-            None => Name::new(self.as_str()),
-            Some(x) => Name::new(x.0),
+            None => self.as_str(),
+            Some(x) => x.0,
         }
     }
 
     pub fn components(self) -> Vec<Name> {
         self.0.split('.').map(Name::new).collect()
     }
+
+    /// Whether this looks like a test module by naming convention: the last component starts
+    /// with `test_` or ends with `_test`, or any component is `tests`. Names like `testing`
+    /// or `contest` are not test modules.
+    pub fn is_test_module(self) -> bool {
+        let mut components = self.0.split('.');
+        let last = components.next_back().unwrap_or_default();
+        last.starts_with("test_")
+            || last.ends_with("_test")
+            || last == "tests"
+            || components.any(|x| x == "tests")
+    }
+
+    /// Collapse runs of dots between components, and drop trailing dots, e.g. `a..b` becomes
+    /// `a.b`. Leading dots mark a relative name, so are kept. Returns `self` if the name is
+    /// already normal, without interning anything.
+    pub fn normalized(self) -> ModuleName {
+        let rest = self.0.trim_start_matches('.');
+        if !rest.contains("..") && !rest.ends_with('.') {
+            return self;
+        }
+        let dots = &self.0[..self.0.len() - rest.len()];
+        Self::from_string(format!(
+            "{dots}{}",
+            itertools::join(rest.split('.').filter(|x| !x.is_empty()), ".")
+        ))
+    }
+
+    /// Progressively longer prefixes of the name, ending with the name itself,
+    /// e.g. `a`, `a.b`, `a.b.c` for `a.b.c`.
+    pub fn prefixes(self) -> impl Iterator<Item = ModuleName> {
+        let ends = self
+            .0
+            .match_indices('.')
+            .map(|x| x.0)
+            .chain(iter::once(self.0.len()))
+            .collect::<Vec<_>>();
+        ends.into_iter().map(move |i| Self::from_str(&self.0[..i]))
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Instant;
+
     use super::*;
 
     #[test]
@@ -260,6 +517,160 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_normalized() {
+        for (x, expected) in [
+            ("a..b", "a.b"),
+            (".a.b", ".a.b"),
+            ("..a...b.", "..a.b"),
+            ("a.b.c", "a.b.c"),
+        ] {
+            let normalized = ModuleName::from_str(x).normalized();
+            assert!(normalized.content_eq(expected), "{x}: got {normalized}");
+        }
+    }
+
+    #[test]
+    fn test_content_eq() {
+        let m = ModuleName::from_str("a.b");
+        assert!(m.content_eq("a.b"));
+        assert!(!m.content_eq("a"));
+        assert!(!m.content_eq("a.b."));
+        assert!(ModuleName::from_str("").content_eq(""));
+    }
+
+    #[test]
+    fn test_ord() {
+        let mut names = ["b", "a.c", "", "a", "_a", "a.b"].map(ModuleName::from_str);
+        names.sort();
+        assert_eq!(
+            names.map(|x| x.as_str().to_owned()),
+            ["", "_a", "a", "a.b", "a.c", "b"]
+        );
+    }
+
+    #[test]
+    fn test_intern_many() {
+        let names = ["a.b", "c", "a.b", "", "..d"];
+        assert_eq!(
+            ModuleName::intern_many(names),
+            names.map(ModuleName::from_str).to_vec()
+        );
+        assert_eq!(ModuleName::intern_many([]), Vec::new());
+    }
+
+    /// A rough benchmark of `intern_many` against calling `from_str` on each name. Run it with
+    /// `cargo test --release bench_intern_many -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_intern_many() {
+        // Module names repeat a lot in a real project, e.g. every file importing `typing`.
+        let names = (0..100_000)
+            .map(|i| format!("pkg{}.module{}", i % 50, i % 1000))
+            .collect::<Vec<_>>();
+        let start = Instant::now();
+        let one_at_a_time = names
+            .iter()
+            .map(|x| ModuleName::from_str(x))
+            .collect::<Vec<_>>();
+        let from_str = start.elapsed();
+        let start = Instant::now();
+        let batched = ModuleName::intern_many(names.iter().map(|x| x.as_str()));
+        let intern_many = start.elapsed();
+        eprintln!(
+            "{} names: from_str {from_str:?}, intern_many {intern_many:?}",
+            names.len()
+        );
+        assert_eq!(batched, one_at_a_time);
+    }
+
+    #[test]
+    fn test_is_test_module() {
+        for x in [
+            "test_foo",
+            "foo_test",
+            "tests",
+            "tests.foo",
+            "pkg.tests.helpers",
+            "pkg.test_foo",
+            "pkg.foo_test",
+        ] {
+            assert!(ModuleName::from_str(x).is_test_module(), "{x}");
+        }
+        for x in [
+            "testing",
+            "pkg.testing",
+            "test",
+            "contest",
+            "test_foo.bar",
+            "foo_tests",
+            "pytest",
+        ] {
+            assert!(!ModuleName::from_str(x).is_test_module(), "{x}");
+        }
+    }
+
+    #[test]
+    fn test_prefixes() {
+        let prefixes = |x| {
+            ModuleName::from_str(x)
+                .prefixes()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(prefixes("a.b.c"), vec!["a", "a.b", "a.b.c"]);
+        assert_eq!(prefixes("a"), vec!["a"]);
+    }
+
+    #[test]
+    fn test_from_names() {
+        let empty = ModuleName::from_names(&[]);
+        assert_eq!(empty, ModuleName::from_str(""));
+        assert_eq!(empty.to_string(), ".");
+        assert_eq!(
+            ModuleName::from_names(&[Name::new_static("a")]),
+            ModuleName::from_str("a")
+        );
+        let names = [
+            Name::new_static("a"),
+            Name::new_static("b"),
+            Name::new_static("c"),
+        ];
+        let m = ModuleName::from_names(&names);
+        assert_eq!(m, ModuleName::from_str("a.b.c"));
+        assert_eq!(m, ModuleName::from_parts(&names));
+        assert_eq!(m.components(), names);
+    }
+
+    #[test]
+    fn test_first_component_str() {
+        for x in ["a.b.c", "a", "", ".a"] {
+            let m = ModuleName::from_str(x);
+            assert_eq!(m.first_component_str(), m.first_component().as_str());
+        }
+        assert_eq!(ModuleName::from_str("a.b.c").first_component_str(), "a");
+    }
+
+    #[test]
+    fn test_as_display_str() {
+        assert_eq!(ModuleName::from_str("").as_display_str(), ".");
+        assert_eq!(ModuleName::from_str("a.b").as_display_str(), "a.b");
+        assert_eq!(
+            ModuleName::from_str("").to_string(),
+            ModuleName::from_str("").as_display_str()
+        );
+    }
+
+    #[test]
+    fn test_eq_str() {
+        let typing = ModuleName::typing();
+        assert!(typing == "typing");
+        assert!(typing == *"typing");
+        assert!(typing != "typing_extensions");
+        assert!(ModuleName::from_str("a.b") != "a");
+        assert!(ModuleName::from_str("") == "");
+    }
+
     #[test]
     fn test_relative() {
         let base = ModuleName::from_str("a.b.c");
@@ -307,10 +718,8 @@ mod tests {
     #[test]
     fn test_from_relative_path() {
         fn assert_module_name(path: &str, expected: &str) {
-            assert_eq!(
-                ModuleName::from_relative_path(Path::new(path)).unwrap(),
-                ModuleName::from_str(expected)
-            );
+            let name = ModuleName::from_relative_path(Path::new(path)).unwrap();
+            assert!(name.content_eq(expected), "{path}: got {name}");
         }
         assert_module_name("foo.py", "foo");
         assert_module_name("foo.pyi", "foo");
@@ -326,4 +735,109 @@ mod tests {
         assert_conversion_error("foo/bar/baz");
         assert_conversion_error("foo/bar/__init__.derp");
     }
+
+    #[test]
+    fn test_from_stub_package_path() {
+        fn assert_module_name(path: &str, expected: &str) {
+            assert_eq!(
+                ModuleName::from_stub_package_path(
+                    &Path::new("/root").join(path),
+                    Path::new("/root")
+                )
+                .unwrap(),
+                ModuleName::from_str(expected)
+            );
+        }
+        assert_module_name("foo-stubs/bar.pyi", "foo.bar");
+        assert_module_name("foo-stubs/__init__.pyi", "foo");
+        assert_module_name("foo-stubs/bar/__init__.pyi", "foo.bar");
+        assert_module_name("foo/bar-stubs/baz.pyi", "foo.bar-stubs.baz");
+        assert_module_name("foo/bar.py", "foo.bar");
+        // A top-level file is not a stub package, even with the suffix.
+        assert_module_name("foo-stubs.pyi", "foo-stubs");
+        assert_module_name("-stubs/bar.pyi", "-stubs.bar");
+
+        assert!(
+            ModuleName::from_stub_package_path(
+                Path::new("/elsewhere/foo-stubs/bar.pyi"),
+                Path::new("/root")
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_from_relative_path_separators() {
+        let expected = ModuleName::from_str("foo.bar.baz");
+        for path in ["foo/bar/baz.py", "foo\\bar\\baz.py", "foo/bar\\baz.py"] {
+            assert_eq!(
+                ModuleName::from_relative_path(Path::new(path)).unwrap(),
+                expected
+            );
+        }
+        assert_eq!(
+            ModuleName::from_relative_path(Path::new("foo\\bar\\__init__.py")).unwrap(),
+            ModuleName::from_str("foo.bar")
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_from_relative_path_windows_components() {
+        // On Windows, `Path` itself splits on both separators.
+        assert_eq!(Path::new("foo\\bar/baz.py").components().count(), 3);
+        assert_eq!(
+            ModuleName::from_relative_path(Path::new("foo\\bar/baz.py")).unwrap(),
+            ModuleName::from_str("foo.bar.baz")
+        );
+    }
+
+    #[test]
+    fn test_module_name_maps() {
+        let names = ["b.c", "a", "c", "b", "a.z"];
+        let insertion_order =
+            |map: &ModuleNameMap<usize>| map.iter().map(|(k, _)| k.to_string()).collect::<Vec<_>>();
+        let lexicographic_order = |map: &SortedModuleNameMap<usize>| {
+            map.iter().map(|(k, _)| k.to_string()).collect::<Vec<_>>()
+        };
+
+        let mut map = names
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (ModuleName::from_str(x), i))
+            .collect::<ModuleNameMap<_>>();
+        assert_eq!(insertion_order(&map), names);
+        // Replacing a value keeps its position.
+        assert_eq!(map.insert(ModuleName::from_str("a"), 10), Some(1));
+        assert_eq!(insertion_order(&map), names);
+        assert_eq!(map.get(ModuleName::from_str("a")), Some(&10));
+        assert!(!map.contains_key(ModuleName::from_str("d")));
+
+        let sorted = names
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, x)| (ModuleName::from_str(x), i))
+            .collect::<SortedModuleNameMap<_>>();
+        assert_eq!(lexicographic_order(&sorted), ["a", "a.z", "b", "b.c", "c"]);
+        let sorted2 = names
+            .iter()
+            .map(|x| (ModuleName::from_str(x), 0))
+            .collect::<SortedModuleNameMap<_>>();
+        assert_eq!(lexicographic_order(&sorted), lexicographic_order(&sorted2));
+        assert_eq!(sorted.len(), names.len());
+    }
+
+    #[test]
+    fn test_tagged_serde() {
+        let module = ModuleName::from_str("a.b.c");
+        let tagged = TaggedModuleName::from(module);
+        let json = serde_json::to_string(&tagged).unwrap();
+        assert_eq!(json, r#"{"module":"a.b.c"}"#);
+        let back: TaggedModuleName = serde_json::from_str(&json).unwrap();
+        assert_eq!(ModuleName::from(back), module);
+        // The untagged form is unchanged.
+        assert_eq!(serde_json::to_string(&module).unwrap(), r#""a.b.c""#);
+        assert!(serde_json::from_str::<TaggedModuleName>(r#""a.b.c""#).is_err());
+    }
 }
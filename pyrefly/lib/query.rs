@@ -7,26 +7,303 @@
 
 //! Query interface for pyrefly. Just experimenting for the moment - not intended for external use.
 
+use std::cmp::Reverse;
 use std::io::Cursor;
+use std::iter;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::ptr;
+use std::slice;
+use std::sync::Arc;
 
 use dupe::Dupe;
+use itertools::Itertools;
+use pyrefly_util::lined_buffer::DisplayPos;
 use pyrefly_util::lined_buffer::DisplayRange;
+use pyrefly_util::lined_buffer::LineNumber;
 use pyrefly_util::prelude::SliceExt;
 use pyrefly_util::prelude::VecExt;
+use pyrefly_util::task_heap::CancellationHandle;
+use pyrefly_util::task_heap::Cancelled;
 use pyrefly_util::visit::Visit;
+use ruff_python_ast::Alias;
+use ruff_python_ast::AnyNodeRef;
+use ruff_python_ast::ExceptHandler;
 use ruff_python_ast::Expr;
+use ruff_python_ast::ExprCall;
+use ruff_python_ast::ExprContext;
+use ruff_python_ast::ExprName;
+use ruff_python_ast::Identifier;
+use ruff_python_ast::NodeKind;
+use ruff_python_ast::Pattern;
+use ruff_python_ast::Stmt;
+use ruff_python_ast::name::Name;
+use ruff_python_ast::visitor::source_order::SourceOrderVisitor;
+use ruff_python_ast::visitor::source_order::TraversalSignal;
+use ruff_python_ast::visitor::source_order::walk_expr;
 use ruff_text_size::Ranged;
+use ruff_text_size::TextRange;
+use ruff_text_size::TextSize;
+use starlark_map::small_map::Entry;
+use starlark_map::small_map::SmallMap;
+use starlark_map::small_set::SmallSet;
 
 use crate::alt::answers::Answers;
+use crate::alt::call::CallStyle;
+use crate::alt::callable::CallArg;
+use crate::alt::callable::CallKeyword;
+use crate::alt::expr::TypeOrExpr;
+use crate::alt::types::class_metadata::ClassMro;
+use crate::binding::binding::Binding;
+use crate::binding::binding::IsAsync;
+use crate::binding::binding::Key;
+use crate::binding::binding::KeyClass;
+use crate::binding::binding::KeyClassMro;
+use crate::binding::binding::KeyExport;
+use crate::binding::binding::KeyFunction;
+use crate::common::symbol_kind::SymbolKind;
+use crate::config::config::ConfigSource;
+use crate::config::error::ErrorConfig;
+use crate::config::error::ErrorDisplayConfig;
 use crate::config::finder::ConfigFinder;
+use crate::error::error::Error;
+use crate::error::kind::ErrorKind;
+use crate::export::definitions::Definitions;
+use crate::export::definitions::DunderAllEntry;
+use crate::graph::index::Idx;
+use crate::module::ignore::Ignore;
 use crate::module::module_info::ModuleInfo;
 use crate::module::module_name::ModuleName;
 use crate::module::module_path::ModulePath;
+use crate::module::module_path::ModulePathDetails;
+use crate::module::short_identifier::ShortIdentifier;
+use crate::python::ast::Ast;
+use crate::python::sys_info::PythonPlatform;
+use crate::python::sys_info::PythonVersion;
 use crate::python::sys_info::SysInfo;
 use crate::state::handle::Handle;
+use crate::state::loader::FindError;
 use crate::state::require::Require;
 use crate::state::state::State;
+use crate::state::state::Transaction;
+use crate::types::callable::Callable;
+use crate::types::callable::FuncMetadata;
+use crate::types::callable::Function;
+use crate::types::callable::Param;
+use crate::types::callable::Params;
 use crate::types::display::TypeDisplayContext;
+use crate::types::quantified::QuantifiedKind;
+use crate::types::tuple::Tuple;
+use crate::types::type_var::PreInferenceVariance;
+use crate::types::type_var::Restriction;
+use crate::types::types::AnyStyle;
+use crate::types::types::BoundMethod;
+use crate::types::types::BoundMethodType;
+use crate::types::types::Forall;
+use crate::types::types::Forallable;
+use crate::types::types::OverloadType;
+use crate::types::types::TParams;
+use crate::types::types::Type;
+
+/// A suppression comment in a file, along with what it suppressed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnoreComment {
+    pub line: LineNumber,
+    /// The error codes listed in the comment, and whether it suppressed an error with that code.
+    pub codes: Vec<(String, bool)>,
+    /// The codes of all the errors the comment suppressed, which for a bare
+    /// `# type: ignore` may be anything.
+    pub suppressed: Vec<String>,
+}
+
+/// An error reported in a committed module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    pub path: ModulePath,
+    pub range: DisplayRange,
+    /// The name of the error kind, e.g. `bad-assignment`.
+    pub code: String,
+    pub message: String,
+}
+
+impl QueryError {
+    fn new(e: &Error) -> Self {
+        Self {
+            path: e.path().dupe(),
+            range: e.display_range().clone(),
+            code: e.error_kind().to_name().to_owned(),
+            message: e.msg(),
+        }
+    }
+}
+
+/// A member of a class, as reported by `Query::class_members`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassMember {
+    pub name: String,
+    /// The type of the member when accessed on an instance. Methods are shown without
+    /// their `self` parameter.
+    pub ty: String,
+    pub kind: SymbolKind,
+    /// The class the member is defined in, which is an ancestor for inherited members.
+    pub defining_class: String,
+}
+
+/// How many of a file's expressions have a known type, as reported by `Query::type_coverage`.
+/// Only the outermost type is considered, so `list[Any]` counts as typed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Coverage {
+    /// Expressions whose type isn't `Any`.
+    pub typed: usize,
+    /// Expressions whose type is `Any` because the user wrote `Any`.
+    pub explicit_any: usize,
+    /// Expressions whose type is `Any` because we couldn't do better, e.g. a missing
+    /// annotation, an error, or an unresolved import.
+    pub unknown: usize,
+}
+
+impl Coverage {
+    pub fn total(&self) -> usize {
+        self.typed + self.explicit_any + self.unknown
+    }
+
+    /// The percentage of expressions that are typed, or 100 if there are no expressions.
+    pub fn percentage(&self) -> f64 {
+        if self.total() == 0 {
+            100.0
+        } else {
+            self.typed as f64 * 100.0 / self.total() as f64
+        }
+    }
+}
+
+/// What a name in `from target import name` refers to, as reported by `Query::resolve_import`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportResolution {
+    /// A symbol exported by the target module, with its type.
+    Member(String),
+    /// A submodule of the target package, with its path.
+    Submodule(ModulePath),
+    /// Neither a member nor a submodule we can find.
+    Unresolved,
+}
+
+/// A type parameter of a generic class or function, as reported by `Query::type_parameters`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeParamInfo {
+    pub name: String,
+    /// Whether this is a `TypeVar`, `ParamSpec` or `TypeVarTuple`.
+    pub kind: QuantifiedKind,
+    pub bound: Option<String>,
+    /// The constraints of a constrained `TypeVar`, empty otherwise.
+    pub constraints: Vec<String>,
+    /// The declared variance. Parameters using PEP 695 syntax or `infer_variance=True`
+    /// are `PUndefined`, since their variance is inferred from how they are used.
+    pub variance: PreInferenceVariance,
+    pub default: Option<String>,
+}
+
+/// The configuration the `ConfigFinder` resolved for a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigSummary {
+    /// Where the config came from, `ConfigSource::Synthetic` if no config file applied
+    /// and defaults were used.
+    pub source: ConfigSource,
+    /// The directories searched for imports, in order, before typeshed and site packages.
+    pub search_path: Vec<PathBuf>,
+    pub site_package_path: Vec<PathBuf>,
+    pub python_version: PythonVersion,
+    pub python_platform: PythonPlatform,
+}
+
+/// Convert a one-indexed line and column to a position in the file.
+fn text_size(module_info: &ModuleInfo, line: u32, col: u32) -> Option<TextSize> {
+    Some(module_info.lined_buffer().from_display_pos(DisplayPos {
+        line: LineNumber::new(line)?,
+        column: NonZeroU32::new(col)?,
+    }))
+}
+
+/// `matching_indices` gives up on finding a longest common subsequence if it would need a table
+/// with more entries than this.
+const MAX_MATCHING_TABLE_SIZE: usize = 1 << 22;
+
+/// The positions of a longest common subsequence of `xs` and `ys`, as `(index in xs, index in
+/// ys)` in increasing order. A common prefix and suffix are matched directly, so the quadratic
+/// search only covers the part in between, and is skipped if that part is too large.
+fn matching_indices<T: PartialEq>(xs: &[T], ys: &[T]) -> Vec<(usize, usize)> {
+    let prefix = xs.iter().zip(ys).take_while(|(x, y)| x == y).count();
+    let suffix = xs[prefix..]
+        .iter()
+        .rev()
+        .zip(ys[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let xs_mid = &xs[prefix..xs.len() - suffix];
+    let ys_mid = &ys[prefix..ys.len() - suffix];
+    let mut res = (0..prefix).map(|i| (i, i)).collect::<Vec<_>>();
+    let width = ys_mid.len() + 1;
+    if (xs_mid.len() + 1).saturating_mul(width) <= MAX_MATCHING_TABLE_SIZE {
+        // `lengths[i * width + j]` is the length of a longest common subsequence of `xs_mid[i..]`
+        // and `ys_mid[j..]`.
+        let mut lengths = vec![0u32; (xs_mid.len() + 1) * width];
+        for i in (0..xs_mid.len()).rev() {
+            for j in (0..ys_mid.len()).rev() {
+                lengths[i * width + j] = if xs_mid[i] == ys_mid[j] {
+                    lengths[(i + 1) * width + j + 1] + 1
+                } else {
+                    lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < xs_mid.len() && j < ys_mid.len() {
+            if xs_mid[i] == ys_mid[j] {
+                res.push((prefix + i, prefix + j));
+                i += 1;
+                j += 1;
+            } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+    }
+    res.extend((0..suffix).map(|k| (xs.len() - suffix + k, ys.len() - suffix + k)));
+    res
+}
+
+/// Sort errors from the given handles by module name, then position, then error kind, so
+/// the order doesn't depend on the order the modules were checked in.
+fn sort_errors(errors: &mut [Error], handles: &[(Handle, Require)]) {
+    let names = handles
+        .iter()
+        .map(|(h, _)| (h.path(), h.module()))
+        .collect::<SmallMap<_, _>>();
+    errors.sort_by_key(|e| {
+        (
+            names.get(e.path()).copied(),
+            e.display_range().start,
+            e.error_kind().to_name(),
+        )
+    });
+}
+
+/// The signature of the function called by `call`, using the chosen overload if it is overloaded.
+fn call_signature(answers: &Answers, call: &ExprCall) -> Option<Callable> {
+    if let Some(callable) = answers.get_chosen_overload_trace(call.arguments.range) {
+        return Some(callable);
+    }
+    let mut callee = (*answers.get_type_trace(call.func.range())?).clone();
+    if let Type::BoundMethod(method) = &callee {
+        callee = method.as_bound_function().to_unbound_callable()?;
+    }
+    match callee {
+        Type::Callable(callable) => Some(*callable),
+        Type::Function(func) => Some(func.signature),
+        _ => None,
+    }
+}
 
 pub struct Query {
     state: State,
@@ -46,59 +323,2225 @@ impl Query {
         Handle::new(name, path, self.sys_info.dupe())
     }
 
+    /// Find the file for a module, using the search path of the config that applies to the
+    /// current directory. The module is not loaded.
+    ///
+    /// Namespace packages, which may be spread across several directories, resolve to the
+    /// first directory on the search path that contributes to them.
+    pub fn find_module(&self, name: ModuleName) -> Option<ModulePath> {
+        let config = self
+            .state
+            .config_finder()
+            .python_file(name, &ModulePath::namespace(PathBuf::from(".")));
+        config.find_import(name, None).ok()
+    }
+
+    /// The configuration that applies to a file. The module name of the file is not known,
+    /// so if no config file applies, this reports the defaults for a file opened directly.
+    pub fn effective_config(&self, path: ModulePath) -> ConfigSummary {
+        let config = self
+            .state
+            .config_finder()
+            .python_file(ModuleName::unknown(), &path);
+        ConfigSummary {
+            source: config.source.clone(),
+            search_path: config
+                .search_path()
+                .chain(&config.fallback_search_path)
+                .cloned()
+                .collect(),
+            site_package_path: config.site_package_path().to_vec(),
+            python_version: config.python_version(),
+            python_platform: config.python_platform().clone(),
+        }
+    }
+
     /// Load the given files and return any errors associated with them
     pub fn add_files(&self, files: Vec<(ModuleName, ModulePath)>) -> Vec<String> {
+        // A fresh handle is never cancelled, so this can't fail
+        self.add_files_cancellable(files, CancellationHandle::new())
+            .unwrap_or_default()
+    }
+
+    /// Like `add_files`, but abandons the check if `cancellation_handle` is cancelled before it
+    /// finishes. A cancelled check is never committed, so it is as though it never happened:
+    /// none of the files are added, and previously added files are unaffected.
+    pub fn add_files_cancellable(
+        &self,
+        files: Vec<(ModuleName, ModulePath)>,
+        cancellation_handle: CancellationHandle,
+    ) -> Result<Vec<String>, Cancelled> {
         let mut transaction = self
             .state
             .new_committable_transaction(Require::Everything, None);
         let handles =
             files.into_map(|(name, file)| (self.make_handle(name, file), Require::Everything));
-        transaction.as_mut().run(&handles);
+        transaction.run_cancellable(&handles, cancellation_handle)?;
         let errors = transaction
             .as_mut()
             .get_errors(handles.iter().map(|(h, _)| h));
         self.state.commit_transaction(transaction);
-        errors.collect_errors().shown.map(|e| {
+        let mut errors = errors.collect_errors().shown;
+        sort_errors(&mut errors, &handles);
+        Ok(errors.map(|e| {
             // We deliberately don't have a Display for `Error`, to encourage doing the right thing.
             // But we just hack something up as this code is experimental.
             let mut s = Cursor::new(Vec::new());
             e.write_line(&mut s, false).unwrap();
             String::from_utf8_lossy(&s.into_inner()).into_owned()
+        }))
+    }
+
+    /// Every shown error in the committed modules, computed one module at a time so that only
+    /// a single module's errors are materialized at once. Modules are visited in path order.
+    ///
+    /// The iterator reads from a snapshot of the committed state, which it holds on to, so
+    /// commits (e.g. `add_files`) will block until it is dropped.
+    pub fn iter_errors(&self) -> impl Iterator<Item = QueryError> + '_ {
+        self.iter_errors_matching(|_| true)
+    }
+
+    /// Every shown error of the given kind in the committed modules, ordered as in `iter_errors`.
+    pub fn errors_for_rule(&self, kind: ErrorKind) -> Vec<QueryError> {
+        self.iter_errors_matching(move |e| e.error_kind() == kind)
+            .collect()
+    }
+
+    fn iter_errors_matching(
+        &self,
+        filter: impl Fn(&Error) -> bool + Copy + 'static,
+    ) -> impl Iterator<Item = QueryError> + '_ {
+        let transaction = self.state.transaction();
+        let mut handles = transaction.handles();
+        handles.retain(|h| !matches!(h.path().details(), ModulePathDetails::BundledTypeshed(_)));
+        handles.sort_by(|a, b| a.path().cmp(b.path()));
+        handles.into_iter().flat_map(move |handle| {
+            transaction
+                .get_errors([&handle])
+                .collect_errors()
+                .shown
+                .into_iter()
+                .filter(filter)
+                .map(|e| QueryError::new(&e))
+        })
+    }
+
+    /// Check the given files as though they targeted `version` rather than the default Python
+    /// version, and return their shown errors, e.g. to find code that would break on an older
+    /// Python. The check runs in a transaction that is then discarded, so this doesn't affect
+    /// the committed state.
+    pub fn errors_under_version(
+        &self,
+        files: Vec<(ModuleName, ModulePath)>,
+        version: PythonVersion,
+    ) -> Vec<QueryError> {
+        let sys_info = SysInfo::new(version, self.sys_info.platform().clone());
+        let mut transaction = self.state.new_transaction(Require::Everything, None);
+        let handles = files.into_map(|(name, path)| {
+            (
+                Handle::new(name, path, sys_info.dupe()),
+                Require::Everything,
+            )
+        });
+        transaction.run(&handles);
+        let mut errors = transaction
+            .get_errors(handles.iter().map(|(h, _)| h))
+            .collect_errors()
+            .shown;
+        sort_errors(&mut errors, &handles);
+        errors.iter().map(QueryError::new).collect()
+    }
+
+    /// The revealed type at each `reveal_type(...)` call in a file, in order.
+    /// These are reported regardless of whether errors are suppressed or disabled.
+    pub fn reveal_types(&self, name: ModuleName, path: ModulePath) -> Vec<(DisplayRange, String)> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let errors = transaction.get_errors([&handle]).collect_errors();
+        let mut res = errors
+            .shown
+            .into_iter()
+            .chain(errors.suppressed)
+            .chain(errors.disabled)
+            .filter(|e| e.error_kind() == ErrorKind::RevealType)
+            .filter_map(|e| {
+                let ty = e.msg_header().strip_prefix("revealed type: ")?.to_owned();
+                Some((e.display_range().clone(), ty))
+            })
+            .collect::<Vec<_>>();
+        res.sort_by(|a, b| a.0.cmp(&b.0));
+        res
+    }
+
+    /// Check that `expr` is assignable to `expected_type`, as though
+    /// `_: expected_type = expr` was written at the end of the module. Names are resolved in
+    /// the module's scope, as it stands at the end of the module. Returns the messages of any
+    /// errors, e.g. incompatible types or names that are not in scope.
+    ///
+    /// The module is checked again with the extra line added, in a transaction that is then
+    /// discarded, so this doesn't affect the committed state.
+    pub fn check_expr_against(
+        &self,
+        context_module: ModuleName,
+        path: ModulePath,
+        expr: &str,
+        expected_type: &str,
+    ) -> Result<(), String> {
+        let mut transaction = self.state.new_transaction(Require::Everything, None);
+        let module_info = transaction
+            .get_module_info(&self.make_handle(context_module, path.dupe()))
+            .ok_or_else(|| format!("Module `{context_module}` is not loaded"))?;
+        let mut source = module_info.contents().as_str().to_owned();
+        if !source.is_empty() && !source.ends_with('\n') {
+            source.push('\n');
+        }
+        let check_line = source.lines().count() as u32 + 1;
+        source.push_str(&format!(
+            "__pyrefly_check_expr__: {expected_type} = ({expr})\n"
+        ));
+        let memory_path = path.as_path().to_owned();
+        transaction.set_memory(vec![(memory_path.clone(), Some(Arc::new(source)))]);
+        let handle = self.make_handle(context_module, ModulePath::memory(memory_path));
+        transaction.run(&[(handle.dupe(), Require::Everything)]);
+        let errors = transaction
+            .get_errors([&handle])
+            .collect_errors()
+            .shown
+            .into_iter()
+            .filter(|e| e.display_range().start.line.get() >= check_line)
+            .map(|e| e.msg())
+            .collect::<Vec<_>>();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+
+    /// The type of every expression in a committed file that we recorded a type for.
+    fn expr_types(&self, handle: &Handle) -> Option<(ModuleInfo, Vec<(TextRange, Arc<Type>)>)> {
+        Self::expr_types_in(&self.state.transaction(), handle)
+    }
+
+    /// Like `expr_types`, but for the file as it is in `transaction`.
+    fn expr_types_in(
+        transaction: &Transaction,
+        handle: &Handle,
+    ) -> Option<(ModuleInfo, Vec<(TextRange, Arc<Type>)>)> {
+        let ast = transaction.get_ast(handle)?;
+        let module_info = transaction.get_module_info(handle)?;
+        let answers = transaction.get_answers(handle)?;
+
+        let mut res = Vec::new();
+        fn f(x: &Expr, answers: &Answers, res: &mut Vec<(TextRange, Arc<Type>)>) {
+            let range = x.range();
+            if let Some(ty) = answers.get_type_trace(range) {
+                res.push((range, ty));
+            }
+            x.recurse(&mut |x| f(x, answers, res));
+        }
+
+        ast.visit(&mut |x| f(x, &answers, &mut res));
+        Some((module_info, res))
+    }
+
+    /// The type of every expression interpolated into an f-string, including those in format
+    /// specs, like `width` in `f"{x:{width}}"`, in source order.
+    pub fn fstring_interpolation_types(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+    ) -> Vec<(DisplayRange, String)> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let (Some(ast), Some(module_info), Some(answers)) = (
+            transaction.get_ast(&handle),
+            transaction.get_module_info(&handle),
+            transaction.get_answers(&handle),
+        ) else {
+            return Vec::new();
+        };
+
+        fn f(x: &Expr, answers: &Answers, res: &mut Vec<(TextRange, Arc<Type>)>) {
+            if let Expr::FString(fstring) = x {
+                fstring.visit(&mut |x: &Expr| {
+                    if let Some(ty) = answers.get_type_trace(x.range()) {
+                        res.push((x.range(), ty));
+                    }
+                });
+            }
+            x.recurse(&mut |x| f(x, answers, res));
+        }
+
+        let mut res = Vec::new();
+        ast.visit(&mut |x| f(x, &answers, &mut res));
+        res.sort_by_key(|(range, _)| range.start());
+        res.into_map(|(range, ty)| {
+            (
+                module_info.display_range(range),
+                answers.for_display(ty.arc_clone()).to_string(),
+            )
+        })
+    }
+
+    /// Every attribute access or subscript, in source order, whose object might be `None`, along
+    /// with the type of the object. Accesses guarded by narrowing, such as inside
+    /// `if x is not None:`, aren't reported, since the object's narrowed type excludes `None`.
+    pub fn optional_accesses(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+    ) -> Vec<(DisplayRange, String)> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let (Some(ast), Some(module_info), Some(answers)) = (
+            transaction.get_ast(&handle),
+            transaction.get_module_info(&handle),
+            transaction.get_answers(&handle),
+        ) else {
+            return Vec::new();
+        };
+
+        fn f(x: &Expr, answers: &Answers, res: &mut Vec<(TextRange, Arc<Type>)>) {
+            let object = match x {
+                Expr::Attribute(x) => Some(&x.value),
+                Expr::Subscript(x) => Some(&x.value),
+                _ => None,
+            };
+            if let Some(object) = object
+                && let Some(ty) = answers.get_type_trace(object.range())
+                && match &*ty {
+                    Type::None => true,
+                    Type::Union(xs) => xs.iter().any(|x| x.is_none()),
+                    _ => false,
+                }
+            {
+                res.push((x.range(), ty));
+            }
+            x.recurse(&mut |x| f(x, answers, res));
+        }
+
+        let mut res = Vec::new();
+        ast.visit(&mut |x| f(x, &answers, &mut res));
+        res.sort_by_key(|(range, _)| range.start());
+        res.into_map(|(range, ty)| {
+            (
+                module_info.display_range(range),
+                answers.for_display(ty.arc_clone()).to_string(),
+            )
         })
     }
 
+    /// Classify the type of every expression in the file, as in `get_types_in_file`, by whether
+    /// it is known, an explicit `Any`, or an inferred `Any`.
+    pub fn type_coverage(&self, name: ModuleName, path: ModulePath) -> Coverage {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let mut coverage = Coverage::default();
+        if let Some(answers) = transaction.get_answers(&handle)
+            && let Some((_, types)) = Self::expr_types_in(&transaction, &handle)
+        {
+            for (_, ty) in types {
+                match answers.for_display(ty.arc_clone()) {
+                    Type::Any(AnyStyle::Explicit) => coverage.explicit_any += 1,
+                    Type::Any(_) => coverage.unknown += 1,
+                    _ => coverage.typed += 1,
+                }
+            }
+        }
+        coverage
+    }
+
     pub fn get_types_in_file(
         &self,
         name: ModuleName,
         path: ModulePath,
     ) -> Option<Vec<(DisplayRange, String)>> {
         let handle = self.make_handle(name, path);
+        let (module_info, types) = self.expr_types(&handle)?;
+        Some(types.into_map(|(range, ty)| {
+            let mut ctx = TypeDisplayContext::new(&[&ty]);
+            ctx.always_display_module_name();
+            (
+                module_info.display_range(range),
+                ctx.display(&ty).to_string(),
+            )
+        }))
+    }
 
-        let transaction = self.state.transaction();
-        let ast = transaction.get_ast(&handle)?;
-        let module_info = transaction.get_module_info(&handle)?;
-        let answers = transaction.get_answers(&handle)?;
+    /// The type of every expression in a file that we recorded a type for, in source order and
+    /// formatted for display, along with the kinds of the AST nodes enclosing the expression.
+    fn expr_types_with_ancestors(
+        transaction: &Transaction,
+        handle: &Handle,
+    ) -> Option<(ModuleInfo, Vec<(Vec<NodeKind>, TextRange, String)>)> {
+        struct Collector<'a> {
+            answers: &'a Answers,
+            ancestors: Vec<NodeKind>,
+            res: Vec<(Vec<NodeKind>, TextRange, String)>,
+        }
+
+        impl<'a> SourceOrderVisitor<'a> for Collector<'_> {
+            fn enter_node(&mut self, node: AnyNodeRef<'a>) -> TraversalSignal {
+                self.ancestors.push(node.kind());
+                TraversalSignal::Traverse
+            }
+
+            fn leave_node(&mut self, _: AnyNodeRef<'a>) {
+                self.ancestors.pop();
+            }
+
+            fn visit_expr(&mut self, x: &'a Expr) {
+                if let Some(ty) = self.answers.get_type_trace(x.range()) {
+                    self.res.push((
+                        self.ancestors.clone(),
+                        x.range(),
+                        self.answers.for_display(ty.arc_clone()).to_string(),
+                    ));
+                }
+                walk_expr(self, x);
+            }
+        }
+
+        let ast = transaction.get_ast(handle)?;
+        let module_info = transaction.get_module_info(handle)?;
+        let answers = transaction.get_answers(handle)?;
+        let mut collector = Collector {
+            answers: &answers,
+            ancestors: Vec::new(),
+            res: Vec::new(),
+        };
+        collector.visit_body(&ast.body);
+        Some((module_info, collector.res))
+    }
 
+    /// The expressions whose inferred type would change if the committed file were replaced by
+    /// `new_source`, as `(range, old_type, new_type)` with ranges in `new_source`. The new source
+    /// is checked in a transaction that is then discarded, so this doesn't affect the committed
+    /// state.
+    ///
+    /// Edits move expressions around, so the expressions of the two files are lined up as a
+    /// longest common subsequence, where two expressions match if they have the same source text
+    /// and are nested in the same kinds of AST nodes, e.g. the `x` in `y = x` inside a function
+    /// only matches another such `x`. This is best-effort: expressions with no partner are not
+    /// reported, and if too much of the file changed, only the unchanged start and end of the
+    /// file are compared.
+    pub fn type_diff(
+        &self,
+        name: ModuleName,
+        old_path: ModulePath,
+        new_source: String,
+    ) -> Vec<(DisplayRange, String, String)> {
+        let Some((old_info, old_types)) = Self::expr_types_with_ancestors(
+            &self.state.transaction(),
+            &self.make_handle(name, old_path.dupe()),
+        ) else {
+            return Vec::new();
+        };
+        let mut transaction = self.state.new_transaction(Require::Everything, None);
+        let memory_path = old_path.as_path().to_owned();
+        transaction.set_memory(vec![(memory_path.clone(), Some(Arc::new(new_source)))]);
+        let handle = self.make_handle(name, ModulePath::memory(memory_path));
+        transaction.run(&[(handle.dupe(), Require::Everything)]);
+        let Some((new_info, new_types)) = Self::expr_types_with_ancestors(&transaction, &handle)
+        else {
+            return Vec::new();
+        };
+
+        let keys = |info: &ModuleInfo, types: &[(Vec<NodeKind>, TextRange, String)]| {
+            types.map(|(ancestors, range, _)| (ancestors.clone(), info.code_at(*range).to_owned()))
+        };
+        let old_keys = keys(&old_info, &old_types);
+        let new_keys = keys(&new_info, &new_types);
         let mut res = Vec::new();
+        for (i, j) in matching_indices(&old_keys, &new_keys) {
+            let (_, _, old_ty) = &old_types[i];
+            let (_, range, new_ty) = &new_types[j];
+            if old_ty != new_ty {
+                res.push((
+                    new_info.display_range(*range),
+                    old_ty.clone(),
+                    new_ty.clone(),
+                ));
+            }
+        }
+        res.sort_by(|a, b| a.0.cmp(&b.0));
+        res
+    }
+
+    /// Return the source of a file, with the type of the outermost expression that ends last on
+    /// each line appended as a comment, e.g. `x = foo()  # type: int`.
+    /// The original text is otherwise untouched, so a multi-line expression is annotated on
+    /// the line where it ends. Lines ending in a `\` continuation are never annotated.
+    pub fn annotate_source(&self, name: ModuleName, path: ModulePath) -> Option<String> {
+        let handle = self.make_handle(name, path);
+        let (module_info, types) = self.expr_types(&handle)?;
+
+        let mut by_line: SmallMap<LineNumber, (DisplayRange, Arc<Type>)> = SmallMap::new();
+        for (range, ty) in types {
+            let range = module_info.display_range(range);
+            match by_line.entry(range.end.line) {
+                Entry::Vacant(e) => {
+                    e.insert((range, ty));
+                }
+                Entry::Occupied(mut e) => {
+                    let best = &e.get().0;
+                    if (range.end, Reverse(range.start)) > (best.end, Reverse(best.start)) {
+                        *e.get_mut() = (range, ty);
+                    }
+                }
+            }
+        }
+
+        let mut res = String::with_capacity(module_info.contents().len());
+        for (i, chunk) in module_info.contents().split_inclusive('\n').enumerate() {
+            let (line, ending) = match chunk.strip_suffix("\r\n") {
+                Some(line) => (line, "\r\n"),
+                None => match chunk.strip_suffix('\n') {
+                    Some(line) => (line, "\n"),
+                    None => (chunk, ""),
+                },
+            };
+            res.push_str(line);
+            if let Some((_, ty)) = by_line.get(&LineNumber::from_zero_indexed(i as u32))
+                && !line.ends_with('\\')
+            {
+                res.push_str(&format!("  # type: {ty}"));
+            }
+            res.push_str(ending);
+        }
+        Some(res)
+    }
+
+    /// Return every import in the file whose target module could not be found.
+    /// Imports inside a `try` block that catches `ImportError` (or `ModuleNotFoundError`)
+    /// are assumed to be optional and are not reported,
+    /// use `unresolved_imports_including_guarded` to include them.
+    pub fn unresolved_imports(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+    ) -> Vec<(ModuleName, DisplayRange)> {
+        self.unresolved_imports_impl(name, path, false)
+    }
+
+    /// Like `unresolved_imports`, but also report imports guarded by `except ImportError`.
+    pub fn unresolved_imports_including_guarded(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+    ) -> Vec<(ModuleName, DisplayRange)> {
+        self.unresolved_imports_impl(name, path, true)
+    }
+
+    fn unresolved_imports_impl(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        include_guarded: bool,
+    ) -> Vec<(ModuleName, DisplayRange)> {
+        let handle = self.make_handle(name, path);
+
+        let transaction = self.state.transaction();
+        let Some(ast) = transaction.get_ast(&handle) else {
+            return Vec::new();
+        };
+        let Some(module_info) = transaction.get_module_info(&handle) else {
+            return Vec::new();
+        };
+
+        fn catches_import_error(handlers: &[ExceptHandler]) -> bool {
+            fn is_import_error(x: &Expr) -> bool {
+                match x {
+                    Expr::Name(x) => x.id == "ImportError" || x.id == "ModuleNotFoundError",
+                    Expr::Tuple(x) => x.elts.iter().any(is_import_error),
+                    _ => false,
+                }
+            }
+            handlers.iter().any(|x| match x {
+                ExceptHandler::ExceptHandler(x) => x.type_.as_deref().is_none_or(is_import_error),
+            })
+        }
+
         fn f(
-            x: &Expr,
+            x: &Stmt,
             module_info: &ModuleInfo,
-            answers: &Answers,
-            res: &mut Vec<(DisplayRange, String)>,
+            guarded: bool,
+            res: &mut Vec<(ModuleName, TextRange, bool)>,
         ) {
-            let range = x.range();
-            if let Some(ty) = answers.get_type_trace(range) {
-                let mut ctx = TypeDisplayContext::new(&[&ty]);
-                ctx.always_display_module_name();
-                res.push((
-                    module_info.display_range(range),
-                    ctx.display(&ty).to_string(),
-                ));
+            match x {
+                Stmt::Import(x) => {
+                    for a in &x.names {
+                        res.push((ModuleName::from_name(&a.name.id), a.name.range, guarded));
+                    }
+                }
+                Stmt::ImportFrom(x) => {
+                    // Invalid relative imports are reported elsewhere.
+                    if let Some(m) = module_info.name().new_maybe_relative(
+                        module_info.path().is_init(),
+                        x.level,
+                        x.module.as_ref().map(|x| &x.id),
+                    ) {
+                        let range = x.module.as_ref().map_or(x.range, |x| x.range);
+                        res.push((m, range, guarded));
+                    }
+                }
+                Stmt::Try(x) => {
+                    let body_guarded = guarded || catches_import_error(&x.handlers);
+                    for s in &x.body {
+                        f(s, module_info, body_guarded, res);
+                    }
+                    for handler in &x.handlers {
+                        match handler {
+                            ExceptHandler::ExceptHandler(h) => {
+                                for s in &h.body {
+                                    f(s, module_info, guarded, res);
+                                }
+                            }
+                        }
+                    }
+                    for s in x.orelse.iter().chain(&x.finalbody) {
+                        f(s, module_info, guarded, res);
+                    }
+                }
+                _ => x.recurse(&mut |x| f(x, module_info, guarded, res)),
+            }
+        }
+
+        let mut imports = Vec::new();
+        for x in &ast.body {
+            f(x, &module_info, false, &mut imports);
+        }
+        imports
+            .into_iter()
+            .filter(|(m, _, guarded)| {
+                (include_guarded || !guarded)
+                    && match transaction.import_handle(&handle, *m, None) {
+                        Ok(_) | Err(FindError::Ignored) => false,
+                        Err(_) => true,
+                    }
+            })
+            .map(|(m, range, _)| (m, module_info.display_range(range)))
+            .collect()
+    }
+
+    /// Return every suppression comment in the file, with the error codes it lists and
+    /// the codes of the errors it actually suppressed.
+    pub fn ignore_comments(&self, name: ModuleName, path: ModulePath) -> Vec<IgnoreComment> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let Some(module_info) = transaction.get_module_info(&handle) else {
+            return Vec::new();
+        };
+        let suppressed_errors = transaction
+            .get_errors([&handle])
+            .collect_errors()
+            .suppressed;
+        let lines = module_info.lined_buffer().lines().collect::<Vec<_>>();
+        module_info
+            .ignore()
+            .lines()
+            .sorted()
+            .map(|line| {
+                let mut suppressed = SmallSet::new();
+                for e in &suppressed_errors {
+                    let range = e.display_range();
+                    if Ignore::covers(line, range.start.line, range.end.line) {
+                        suppressed.insert(e.error_kind().to_name().to_owned());
+                    }
+                }
+                let codes = lines
+                    .get(line.to_zero_indexed() as usize)
+                    .map_or_else(Vec::new, |x| Ignore::get_suppression_codes(x))
+                    .into_map(|code| {
+                        let used = suppressed.contains(&code);
+                        (code, used)
+                    });
+                IgnoreComment {
+                    line,
+                    codes,
+                    suppressed: suppressed.into_iter().collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Whether the name at the given (one-indexed) line and column is only bound inside
+    /// `if TYPE_CHECKING:` blocks, and so is unavailable at runtime.
+    /// Names that are bound anywhere else in their scope, or not bound in this file at all
+    /// (e.g. builtins), are not type-checking only. Returns `None` if there is no name there.
+    pub fn is_type_checking_only(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<bool> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+
+        let covering_nodes = Ast::locate_node(&ast, position);
+        let name = match covering_nodes.first()? {
+            AnyNodeRef::ExprName(x) => &x.id,
+            AnyNodeRef::Identifier(_)
+                if matches!(covering_nodes.get(1), Some(AnyNodeRef::Parameter(_))) =>
+            {
+                return Some(false);
+            }
+            AnyNodeRef::Identifier(x) => &x.id,
+            _ => return None,
+        };
+
+        fn is_type_checking(x: &Expr) -> bool {
+            match x {
+                Expr::Name(x) => x.id == "TYPE_CHECKING",
+                Expr::Attribute(x) => x.attr.as_str() == "TYPE_CHECKING",
+                _ => false,
+            }
+        }
+
+        fn target_binds(x: &Expr, name: &str) -> bool {
+            match x {
+                Expr::Name(x) => x.id == name,
+                Expr::Tuple(x) => x.elts.iter().any(|x| target_binds(x, name)),
+                Expr::List(x) => x.elts.iter().any(|x| target_binds(x, name)),
+                Expr::Starred(x) => target_binds(&x.value, name),
+                _ => false,
+            }
+        }
+
+        // For every binding of `name` in the scope, record whether it is under `TYPE_CHECKING`.
+        fn f(x: &Stmt, name: &str, type_checking: bool, res: &mut Vec<bool>) {
+            let binds = match x {
+                Stmt::FunctionDef(x) => x.name.id == name,
+                Stmt::ClassDef(x) => x.name.id == name,
+                Stmt::Import(x) => x.names.iter().any(|a| match &a.asname {
+                    Some(asname) => asname.id == name,
+                    None => a.name.id.as_str().split('.').next() == Some(name),
+                }),
+                Stmt::ImportFrom(x) => x
+                    .names
+                    .iter()
+                    .any(|a| a.asname.as_ref().unwrap_or(&a.name).id == name),
+                Stmt::Assign(x) => x.targets.iter().any(|x| target_binds(x, name)),
+                Stmt::AnnAssign(x) => target_binds(&x.target, name),
+                Stmt::AugAssign(x) => target_binds(&x.target, name),
+                Stmt::For(x) => target_binds(&x.target, name),
+                Stmt::With(x) => x.items.iter().any(|x| {
+                    x.optional_vars
+                        .as_deref()
+                        .is_some_and(|x| target_binds(x, name))
+                }),
+                _ => false,
+            };
+            if binds {
+                res.push(type_checking);
+            }
+            match x {
+                // These introduce a new scope.
+                Stmt::FunctionDef(_) | Stmt::ClassDef(_) => {}
+                Stmt::If(x) => {
+                    let body_type_checking = type_checking || is_type_checking(&x.test);
+                    for s in &x.body {
+                        f(s, name, body_type_checking, res);
+                    }
+                    for clause in &x.elif_else_clauses {
+                        for s in &clause.body {
+                            f(s, name, type_checking, res);
+                        }
+                    }
+                }
+                _ => x.recurse(&mut |x| f(x, name, type_checking, res)),
+            }
+        }
+
+        // The scopes enclosing the position, innermost first.
+        let mut scopes = Vec::new();
+        for node in &covering_nodes {
+            match node {
+                AnyNodeRef::StmtFunctionDef(x)
+                    if x.body.first().is_some_and(|s| s.start() <= position) =>
+                {
+                    if x.parameters.iter().any(|p| p.name().id == *name) {
+                        return Some(false);
+                    }
+                    scopes.push(x.body.as_slice());
+                }
+                AnyNodeRef::StmtClassDef(x)
+                    if x.body.first().is_some_and(|s| s.start() <= position) =>
+                {
+                    scopes.push(x.body.as_slice());
+                }
+                _ => {}
+            }
+        }
+        scopes.push(ast.body.as_slice());
+
+        for scope in scopes {
+            let mut bindings = Vec::new();
+            for x in scope {
+                f(x, name.as_str(), false, &mut bindings);
+            }
+            if !bindings.is_empty() {
+                return Some(bindings.iter().all(|x| *x));
+            }
+        }
+        Some(false)
+    }
+
+    /// The declared or inferred return type of the innermost function containing the given
+    /// (one-indexed) line and column, or `None` if the position isn't inside a function.
+    pub fn enclosing_return_type(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<String> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let func = Ast::locate_node(&ast, position)
+            .into_iter()
+            .find_map(|x| match x {
+                AnyNodeRef::StmtFunctionDef(x) => Some(x),
+                _ => None,
+            })?;
+        let key = Key::ReturnType(ShortIdentifier::new(&func.name));
+        let bindings = transaction.get_bindings(&handle)?;
+        if !bindings.is_valid_key(&key) {
+            return None;
+        }
+        let answers = transaction.get_answers(&handle)?;
+        let ty = answers.for_display(answers.get_idx(bindings.key_to_idx(&key))?.arc_clone_ty());
+        Some(ty.to_string())
+    }
+
+    /// For the function definition containing the given (one-indexed) line and column, the type
+    /// before any decorators are applied, followed by the type after each decorator, innermost first.
+    /// Decorators that are understood specially, such as `@property`, don't contribute a step.
+    pub fn decorator_effects(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<Vec<String>> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let func = Ast::locate_node(&ast, position)
+            .into_iter()
+            .find_map(|x| match x {
+                AnyNodeRef::StmtFunctionDef(x) => Some(x),
+                _ => None,
+            })?;
+        let bindings = transaction.get_bindings(&handle)?;
+        let answers = transaction.get_answers(&handle)?;
+        let decorated = answers
+            .get_idx(bindings.try_key_to_idx(&KeyFunction(ShortIdentifier::new(&func.name)))?)?;
+        let steps = if decorated.decorator_steps.is_empty() {
+            slice::from_ref(&decorated.ty)
+        } else {
+            decorated.decorator_steps.as_slice()
+        };
+        Some(steps.map(|ty| answers.for_display(ty.clone()).to_string()))
+    }
+
+    /// The `(name, type)` of each parameter of the function whose definition or call is at the
+    /// given (one-indexed) line and column. At a call of an overloaded function, the parameters
+    /// of the chosen overload are used. `*args` and `**kwargs` are given as their container types,
+    /// e.g. `tuple[int, ...]`. Anonymous positional-only parameters have an empty name.
+    pub fn parameter_types(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<Vec<(String, String)>> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let bindings = transaction.get_bindings(&handle)?;
+        let answers = transaction.get_answers(&handle)?;
+        let show = |ty: Type| answers.for_display(ty).to_string();
+        match Ast::locate_node(&ast, position)
+            .into_iter()
+            .find(|x| matches!(x, AnyNodeRef::StmtFunctionDef(_) | AnyNodeRef::ExprCall(_)))?
+        {
+            AnyNodeRef::StmtFunctionDef(func) => func
+                .parameters
+                .iter()
+                .map(|param| {
+                    let key = Key::Definition(ShortIdentifier::new(param.name()));
+                    if !bindings.is_valid_key(&key) {
+                        return None;
+                    }
+                    let ty = answers.get_idx(bindings.key_to_idx(&key))?.arc_clone_ty();
+                    Some((param.name().id.to_string(), show(ty)))
+                })
+                .collect(),
+            AnyNodeRef::ExprCall(call) => {
+                let Params::List(params) = call_signature(&answers, call)?.params else {
+                    return None;
+                };
+                Some(params.items().map(|param| {
+                    let (name, ty) = match param {
+                        Param::PosOnly(name, ty, _) => (name.as_ref(), show(ty.clone())),
+                        Param::Pos(name, ty, _) | Param::KwOnly(name, ty, _) => {
+                            (Some(name), show(ty.clone()))
+                        }
+                        Param::VarArg(name, Type::Unpack(box ty)) => (
+                            name.as_ref(),
+                            show(Type::Tuple(Tuple::unpacked(
+                                Vec::new(),
+                                ty.clone(),
+                                Vec::new(),
+                            ))),
+                        ),
+                        Param::VarArg(name, ty @ Type::Args(_))
+                        | Param::Kwargs(name, Type::Unpack(box ty))
+                        | Param::Kwargs(name, ty @ Type::Kwargs(_)) => {
+                            (name.as_ref(), show(ty.clone()))
+                        }
+                        Param::VarArg(name, ty) => (
+                            name.as_ref(),
+                            show(Type::Tuple(Tuple::unbounded(ty.clone()))),
+                        ),
+                        Param::Kwargs(name, ty) => {
+                            (name.as_ref(), format!("dict[str, {}]", show(ty.clone())))
+                        }
+                    };
+                    (name.map_or_else(String::new, |x| x.to_string()), ty)
+                }))
             }
-            x.recurse(&mut |x| f(x, module_info, answers, res));
+            _ => None,
         }
+    }
 
-        ast.visit(&mut |x| f(x, &module_info, &answers, &mut res));
+    /// The method resolution order of the class whose definition contains the given (one-indexed)
+    /// line and column, starting with the class itself and ending with `object`.
+    /// If the inheritance chain can't be linearized, an `invalid-inheritance` error is reported
+    /// and this returns the part of the MRO computed before that point.
+    /// Returns `None` for a class that inherits from itself.
+    pub fn get_mro(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<Vec<String>> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let class_def = Ast::locate_node(&ast, position)
+            .into_iter()
+            .find_map(|x| match x {
+                AnyNodeRef::StmtClassDef(x) => Some(x),
+                _ => None,
+            })?;
+        let bindings = transaction.get_bindings(&handle)?;
+        let answers = transaction.get_answers(&handle)?;
+        let cls = answers
+            .get_idx(bindings.try_key_to_idx(&KeyClass(ShortIdentifier::new(&class_def.name)))?)?
+            .0
+            .clone()?;
+        let mro = answers.get_idx(bindings.key_to_idx(&KeyClassMro(cls.index())))?;
+        if matches!(&*mro, ClassMro::Cyclic) {
+            return None;
+        }
+        let mut res = vec![cls.name().to_string()];
+        res.extend(mro.ancestors_no_object().iter().map(|x| x.to_string()));
+        res.push("object".to_owned());
         Some(res)
     }
+
+    /// The members of the class whose body contains the given (one-indexed) line and column,
+    /// followed by the members it inherits, in MRO order. A member that is overridden is only
+    /// reported for the class that overrides it, and members of `object` are left out.
+    /// Names that only appear as strings in `__slots__` are not members, since they are never
+    /// declared in the class body.
+    pub fn class_members(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<Vec<ClassMember>> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let class_def = Ast::locate_node(&ast, position)
+            .into_iter()
+            .find_map(|x| match x {
+                AnyNodeRef::StmtClassDef(x) => Some(x),
+                _ => None,
+            })?;
+        let bindings = transaction.get_bindings(&handle)?;
+        let answers = transaction.get_answers(&handle)?;
+        let cls = answers
+            .get_idx(bindings.try_key_to_idx(&KeyClass(ShortIdentifier::new(&class_def.name)))?)?
+            .0
+            .clone()?;
+        transaction.ad_hoc_solve(&handle, |solver| {
+            let instance = solver.instantiate(&cls);
+            let mro = solver.get_mro_for_class(&cls);
+            let mut seen = SmallSet::new();
+            let mut res = Vec::new();
+            for c in
+                iter::once(&cls).chain(mro.ancestors_no_object().iter().map(|x| x.class_object()))
+            {
+                for field_name in c.fields() {
+                    if !seen.insert(field_name) {
+                        continue;
+                    }
+                    let Some(field) =
+                        solver.get_field_from_current_class_only(c, field_name, false)
+                    else {
+                        continue;
+                    };
+                    let mut ty = solver.type_of_attr_get(
+                        &instance,
+                        field_name,
+                        TextRange::default(),
+                        &solver.error_swallower(),
+                        None,
+                        "Query::class_members",
+                    );
+                    if let Type::BoundMethod(method) = &ty
+                        && let Some(unbound) = method.as_bound_function().to_unbound_callable()
+                    {
+                        ty = unbound;
+                    }
+                    res.push(ClassMember {
+                        name: field_name.to_string(),
+                        ty: solver.for_display(ty).to_string(),
+                        kind: field.symbol_kind(),
+                        defining_class: c.name().to_string(),
+                    });
+                }
+            }
+            res
+        })
+    }
+
+    /// The declared type of the parameter that the keyword argument at the given (one-indexed)
+    /// line and column is passed to. A keyword that is absorbed by `**kwargs` gets the value
+    /// type of `**kwargs`. At a call of an overloaded function, the chosen overload is used.
+    pub fn keyword_arg_type(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<String> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let covering = Ast::locate_node(&ast, position);
+        let keyword = covering.iter().find_map(|x| match x {
+            AnyNodeRef::Keyword(x) => Some(*x),
+            _ => None,
+        })?;
+        let arg = keyword.arg.as_ref()?;
+        let call = covering.iter().find_map(|x| match x {
+            AnyNodeRef::ExprCall(x) if x.arguments.keywords.iter().any(|k| ptr::eq(k, keyword)) => {
+                Some(*x)
+            }
+            _ => None,
+        })?;
+        let answers = transaction.get_answers(&handle)?;
+        let Params::List(params) = call_signature(&answers, call)?.params else {
+            return None;
+        };
+        let ty = params
+            .items()
+            .iter()
+            .find_map(|param| match param {
+                Param::Pos(name, ty, _) | Param::KwOnly(name, ty, _) if name == &arg.id => Some(ty),
+                _ => None,
+            })
+            .or_else(|| {
+                params.items().iter().find_map(|param| match param {
+                    Param::Kwargs(_, ty) => Some(ty),
+                    _ => None,
+                })
+            })?;
+        Some(answers.for_display(ty.clone()).to_string())
+    }
+
+    /// The type of the `self` or `cls` parameter of the method containing the given
+    /// (one-indexed) line and column. Returns `None` outside a method, or in a `@staticmethod`.
+    pub fn enclosing_self_type(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<String> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let func = Ast::locate_node(&ast, position)
+            .into_iter()
+            .tuple_windows()
+            .find_map(|x| match x {
+                (AnyNodeRef::StmtFunctionDef(func), AnyNodeRef::StmtClassDef(_)) => Some(func),
+                _ => None,
+            })?;
+        let bindings = transaction.get_bindings(&handle)?;
+        let answers = transaction.get_answers(&handle)?;
+        let decorated = answers
+            .get_idx(bindings.try_key_to_idx(&KeyFunction(ShortIdentifier::new(&func.name)))?)?;
+        if decorated.metadata.flags.is_staticmethod {
+            return None;
+        }
+        let param = func
+            .parameters
+            .posonlyargs
+            .iter()
+            .chain(&func.parameters.args)
+            .next()?;
+        let key = Key::Definition(ShortIdentifier::new(&param.parameter.name));
+        if !bindings.is_valid_key(&key) {
+            return None;
+        }
+        let ty = answers.for_display(answers.get_idx(bindings.key_to_idx(&key))?.arc_clone_ty());
+        Some(ty.to_string())
+    }
+
+    /// The kind of symbol that the name at the given (one-indexed) line and column refers to,
+    /// e.g. so an editor can pick an icon. The kind comes from the definition reaching that
+    /// position, and is refined by looking at the definition site, so a function defined directly
+    /// in a class body is reported as a `Method`.
+    pub fn symbol_kind(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<SymbolKind> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let (metadata, definition, _) = transaction.find_definition(&handle, position, false)?;
+        let kind = metadata.symbol_kind()?;
+
+        let definition_handle = self.make_handle(
+            definition.module_info.name(),
+            definition.module_info.path().dupe(),
+        );
+        let ast = transaction
+            .get_ast(&definition_handle)
+            .unwrap_or_else(|| Arc::new(Ast::parse(definition.module_info.contents()).0));
+        match Ast::locate_node(&ast, definition.range.start()).as_slice() {
+            [
+                AnyNodeRef::Identifier(_),
+                AnyNodeRef::StmtFunctionDef(func),
+                AnyNodeRef::StmtClassDef(_),
+                ..,
+            ] if func.name.range == definition.range => Some(SymbolKind::Method),
+            [
+                AnyNodeRef::Identifier(_),
+                AnyNodeRef::StmtFunctionDef(func),
+                ..,
+            ] if func.name.range == definition.range => Some(SymbolKind::Function),
+            [AnyNodeRef::Identifier(_), AnyNodeRef::StmtClassDef(cls), ..]
+                if cls.name.range == definition.range =>
+            {
+                Some(SymbolKind::Class)
+            }
+            _ => Some(kind),
+        }
+    }
+
+    /// The explicit type aliases defined in a module, as `(alias_name, aliased_type)` pairs in
+    /// source order. This covers `X: TypeAlias = ...` and `type X = ...`, but not implicit aliases.
+    /// References to an alias within its own definition are shown by name.
+    pub fn get_type_aliases(&self, name: ModuleName, path: ModulePath) -> Vec<(String, String)> {
+        fn is_type_alias_annotation(x: &Expr) -> bool {
+            match x {
+                Expr::Name(x) => x.id == "TypeAlias",
+                Expr::Attribute(x) => x.attr.as_str() == "TypeAlias",
+                _ => false,
+            }
+        }
+
+        fn f<'a>(x: &'a Stmt, res: &mut Vec<&'a ExprName>) {
+            match x {
+                Stmt::TypeAlias(x) if let Expr::Name(name) = &*x.name => res.push(name),
+                Stmt::AnnAssign(x)
+                    if let Expr::Name(name) = &*x.target
+                        && is_type_alias_annotation(&x.annotation) =>
+                {
+                    res.push(name)
+                }
+                _ => x.recurse(&mut |x| f(x, res)),
+            }
+        }
+
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let (Some(ast), Some(bindings), Some(answers)) = (
+            transaction.get_ast(&handle),
+            transaction.get_bindings(&handle),
+            transaction.get_answers(&handle),
+        ) else {
+            return Vec::new();
+        };
+        let mut names = Vec::new();
+        for x in &ast.body {
+            f(x, &mut names);
+        }
+        names
+            .into_iter()
+            .filter_map(|x| {
+                let key = Key::Definition(ShortIdentifier::expr_name(x));
+                if !bindings.is_valid_key(&key) {
+                    return None;
+                }
+                let ty = answers.get_idx(bindings.key_to_idx(&key))?.arc_clone_ty();
+                let ta = match &ty {
+                    Type::TypeAlias(ta)
+                    | Type::Forall(box Forall {
+                        body: Forallable::TypeAlias(ta),
+                        ..
+                    }) => ta,
+                    _ => return None,
+                };
+                let aliased = match answers.for_display(ta.as_type()) {
+                    Type::Type(box t) => t,
+                    t => t,
+                };
+                // A recursive alias refers to itself by name.
+                let mut ctx = TypeDisplayContext::new(&[&aliased]);
+                ctx.alias_by_name(x.id.clone());
+                Some((x.id.to_string(), ctx.display(&aliased).to_string()))
+            })
+            .collect()
+    }
+
+    /// The imports in a module that are never referenced, as the imported module and the range
+    /// of the alias. Names listed in `__all__` and explicit re-exports such as `import x as x`
+    /// count as used, and nothing is reported for an `__init__` module, whose imports are
+    /// usually there to be re-exported.
+    pub fn unused_imports(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+    ) -> Vec<(ModuleName, DisplayRange)> {
+        // An import that binds `bound` to `key`, and whether it is an explicit re-export.
+        struct Import<'a> {
+            module: ModuleName,
+            alias: &'a Alias,
+            bound: Name,
+            key: Key,
+            reexport: bool,
+        }
+
+        fn f<'a>(x: &'a Stmt, module_info: &ModuleInfo, res: &mut Vec<Import<'a>>) {
+            match x {
+                Stmt::Import(x) => {
+                    for alias in &x.names {
+                        let module = ModuleName::from_name(&alias.name.id);
+                        res.push(match &alias.asname {
+                            Some(asname) => Import {
+                                module,
+                                alias,
+                                bound: asname.id.clone(),
+                                key: Key::Definition(ShortIdentifier::new(asname)),
+                                reexport: asname.id == alias.name.id,
+                            },
+                            None => Import {
+                                module,
+                                alias,
+                                bound: module.first_component(),
+                                key: Key::Import(module.first_component(), alias.name.range),
+                                reexport: false,
+                            },
+                        });
+                    }
+                }
+                Stmt::ImportFrom(x) => {
+                    let Some(module) = module_info.name().new_maybe_relative(
+                        module_info.path().is_init(),
+                        x.level,
+                        x.module.as_ref().map(|x| &x.id),
+                    ) else {
+                        return;
+                    };
+                    for alias in &x.names {
+                        if &alias.name == "*" {
+                            continue;
+                        }
+                        let asname = alias.asname.as_ref().unwrap_or(&alias.name);
+                        res.push(Import {
+                            module,
+                            alias,
+                            bound: asname.id.clone(),
+                            key: Key::Definition(ShortIdentifier::new(asname)),
+                            reexport: alias.asname.is_some() && asname.id == alias.name.id,
+                        });
+                    }
+                }
+                _ => x.recurse(&mut |x| f(x, module_info, res)),
+            }
+        }
+
+        // The keys a binding refers to without computing anything new.
+        fn forwards(x: &Binding, res: &mut Vec<Idx<Key>>) {
+            match x {
+                Binding::Forward(k)
+                | Binding::Narrow(k, _, _)
+                | Binding::Pin(k, _)
+                | Binding::Module(_, _, Some(k)) => res.push(*k),
+                Binding::Default(k, x) => {
+                    res.push(*k);
+                    forwards(x, res);
+                }
+                Binding::Phi(ks) => res.extend(ks.iter().copied()),
+                _ => {}
+            }
+        }
+
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let (Some(ast), Some(bindings), Some(module_info)) = (
+            transaction.get_ast(&handle),
+            transaction.get_bindings(&handle),
+            transaction.get_module_info(&handle),
+        ) else {
+            return Vec::new();
+        };
+        if module_info.path().is_init() {
+            return Vec::new();
+        }
+
+        // Every key reachable from a use of a name.
+        let mut used = SmallSet::new();
+        let mut todo = bindings
+            .keys::<Key>()
+            .filter(|idx| matches!(bindings.idx_to_key(*idx), Key::BoundName(_)))
+            .collect::<Vec<_>>();
+        while let Some(idx) = todo.pop() {
+            if used.insert(idx) {
+                forwards(bindings.get(idx), &mut todo);
+            }
+        }
+
+        let definitions = Definitions::new(
+            &ast.body,
+            module_info.name(),
+            module_info.path().is_init(),
+            &self.sys_info,
+        );
+        let dunder_all = definitions
+            .dunder_all
+            .iter()
+            .filter_map(|x| match x {
+                DunderAllEntry::Name(_, name) => Some(name),
+                _ => None,
+            })
+            .collect::<SmallSet<_>>();
+
+        let mut imports = Vec::new();
+        for x in &ast.body {
+            f(x, &module_info, &mut imports);
+        }
+        imports
+            .into_iter()
+            .filter(|x| {
+                !x.reexport
+                    && !dunder_all.contains(&x.bound)
+                    && bindings.is_valid_key(&x.key)
+                    && !used.contains(&bindings.key_to_idx(&x.key))
+            })
+            .map(|x| (x.module, module_info.display_range(x.alias.range)))
+            .collect()
+    }
+
+    /// Every call in a module, in source order, along with the location of the definition being
+    /// called. The callee is `None` when it can't be found statically, e.g. because it is the
+    /// result of another call, or an attribute of something whose type is `Any`.
+    pub fn call_edges(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+    ) -> Vec<(DisplayRange, Option<(ModulePath, DisplayRange)>)> {
+        fn f<'a>(x: &'a Expr, res: &mut Vec<&'a ExprCall>) {
+            if let Expr::Call(call) = x {
+                res.push(call);
+            }
+            x.recurse(&mut |x| f(x, res));
+        }
+
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let (Some(ast), Some(module_info)) = (
+            transaction.get_ast(&handle),
+            transaction.get_module_info(&handle),
+        ) else {
+            return Vec::new();
+        };
+        let mut calls = Vec::new();
+        ast.visit(&mut |x| f(x, &mut calls));
+        calls.sort_by_key(|x| x.range.start());
+        calls.into_map(|call| {
+            let callee = match &*call.func {
+                Expr::Name(x) => Some(x.range.start()),
+                Expr::Attribute(x) => Some(x.attr.range.start()),
+                _ => None,
+            }
+            .and_then(|position| transaction.find_definition(&handle, position, true))
+            .map(|(_, definition, _)| {
+                (
+                    definition.module_info.path().dupe(),
+                    definition.module_info.display_range(definition.range),
+                )
+            });
+            (module_info.display_range(call.range), callee)
+        })
+    }
+
+    /// Whether the type at the `from` position is assignable to the type at the `to` position,
+    /// where positions are (one-indexed) line and column pairs. Returns `None` if either
+    /// position doesn't have a type, e.g. because it isn't on an expression.
+    pub fn is_assignable(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        from: (u32, u32),
+        to: (u32, u32),
+    ) -> Option<bool> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let module_info = transaction.get_module_info(&handle)?;
+        let type_at =
+            |(line, col)| transaction.get_type_at(&handle, text_size(&module_info, line, col)?);
+        let from = type_at(from)?;
+        let to = type_at(to)?;
+        transaction.ad_hoc_solve(&handle, |solver| solver.is_subset_eq(&from, &to))
+    }
+
+    /// The signatures of the overloads of the function named at the given (one-indexed) line
+    /// and column, in declaration order. The position may be on a use of the function, a method
+    /// attribute, or any of its `def`s. A function that isn't overloaded gives just its own
+    /// signature, and something that isn't a function gives `None`.
+    pub fn get_overloads(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<Vec<String>> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let bindings = transaction.get_bindings(&handle)?;
+        let answers = transaction.get_answers(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+
+        let covering_nodes = Ast::locate_node(&ast, position);
+        let ty = match covering_nodes.as_slice() {
+            [AnyNodeRef::ExprName(x), ..] => {
+                let key = Key::BoundName(ShortIdentifier::expr_name(x));
+                if !bindings.is_valid_key(&key) {
+                    return None;
+                }
+                answers.get_idx(bindings.key_to_idx(&key))?.arc_clone_ty()
+            }
+            [AnyNodeRef::Identifier(_), AnyNodeRef::ExprAttribute(x), ..] => {
+                answers.get_type_trace(x.range)?.arc_clone()
+            }
+            [
+                AnyNodeRef::Identifier(_),
+                AnyNodeRef::StmtFunctionDef(func),
+                ..,
+            ] => {
+                // Only the last `def` of an overloaded function has the overloaded type.
+                let mut idx =
+                    bindings.try_key_to_idx(&KeyFunction(ShortIdentifier::new(&func.name)))?;
+                while let Some(successor) = bindings.get(idx).successor
+                    && answers.get_idx(idx)?.metadata.flags.is_overload
+                {
+                    idx = successor;
+                }
+                let key = Key::Definition(ShortIdentifier::new(&bindings.get(idx).def.name));
+                answers.get_idx(bindings.key_to_idx(&key))?.arc_clone_ty()
+            }
+            _ => return None,
+        };
+        let ty = match answers.for_display(ty) {
+            Type::BoundMethod(box BoundMethod { func, .. }) => func.as_type(),
+            ty => ty,
+        };
+        match ty {
+            Type::Overload(overload) => Some(
+                overload
+                    .signatures
+                    .iter()
+                    .map(|x| x.as_type().to_string())
+                    .collect(),
+            ),
+            Type::Function(_) | Type::Callable(_) | Type::Forall(_) => Some(vec![ty.to_string()]),
+            _ => None,
+        }
+    }
+
+    /// The type of the elements produced by the innermost comprehension or generator expression
+    /// at the given (one-indexed) line and column. For a dict comprehension, this is the key and
+    /// value types, separated by a comma.
+    pub fn comprehension_element_type(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<String> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let answers = transaction.get_answers(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let (range, arity) =
+            Ast::locate_node(&ast, position)
+                .into_iter()
+                .find_map(|x| match x {
+                    AnyNodeRef::ExprListComp(x) => Some((x.range, 1)),
+                    AnyNodeRef::ExprSetComp(x) => Some((x.range, 1)),
+                    AnyNodeRef::ExprGenerator(x) => Some((x.range, 1)),
+                    AnyNodeRef::ExprDictComp(x) => Some((x.range, 2)),
+                    _ => None,
+                })?;
+        // The element types are the leading type arguments of the `list`, `set`, `dict`
+        // or `Generator` that the comprehension produces.
+        match answers.for_display(answers.get_type_trace(range)?.arc_clone()) {
+            Type::ClassType(cls) if cls.targs().len() >= arity => {
+                Some(cls.targs().as_slice()[..arity].iter().join(", "))
+            }
+            _ => None,
+        }
+    }
+
+    /// Explain how the innermost call at the given (one-indexed) line and column was resolved.
+    /// If the call succeeds, this is the signature it matched, which for an overloaded function
+    /// is the first overload that accepts the arguments. Otherwise, each signature that was
+    /// tried is listed, in order, followed by the errors that calling it would produce.
+    ///
+    /// Arguments are checked using the types recorded for them, so an argument whose type
+    /// depends on the parameter it is passed to (e.g. `[]`) is not re-inferred per overload.
+    pub fn explain_call(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<String> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let answers = transaction.get_answers(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let call = Ast::locate_node(&ast, position)
+            .into_iter()
+            .find_map(|x| match x {
+                AnyNodeRef::ExprCall(x) => Some(x),
+                _ => None,
+            })?;
+
+        let callee = answers.get_type_trace(call.func.range())?.arc_clone();
+        let signature = |overload: &OverloadType, metadata: &FuncMetadata| match overload {
+            OverloadType::Callable(signature) => BoundMethodType::Function(Function {
+                signature: signature.clone(),
+                metadata: metadata.clone(),
+            }),
+            OverloadType::Forall(forall) => BoundMethodType::Forall(forall.clone()),
+        };
+        let candidates: Vec<Type> = match callee {
+            Type::Overload(overload) => overload
+                .signatures
+                .iter()
+                .map(|x| signature(x, &overload.metadata).as_type())
+                .collect(),
+            Type::BoundMethod(box BoundMethod {
+                obj,
+                func: BoundMethodType::Overload(overload),
+            }) => overload
+                .signatures
+                .iter()
+                .map(|x| {
+                    Type::BoundMethod(Box::new(BoundMethod {
+                        obj: obj.clone(),
+                        func: signature(x, &overload.metadata),
+                    }))
+                })
+                .collect(),
+            ty => vec![ty],
+        };
+        let arg_types = call
+            .arguments
+            .args
+            .iter()
+            .map(|x| {
+                let value = match x {
+                    Expr::Starred(x) => &x.value,
+                    _ => x,
+                };
+                Some(answers.get_type_trace(value.range())?.arc_clone())
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let keyword_types = call
+            .arguments
+            .keywords
+            .iter()
+            .map(|x| Some(answers.get_type_trace(x.value.range())?.arc_clone()))
+            .collect::<Option<Vec<_>>>()?;
+        let args = call
+            .arguments
+            .args
+            .iter()
+            .zip(&arg_types)
+            .map(|(x, ty)| match x {
+                Expr::Starred(starred) => {
+                    CallArg::Star(TypeOrExpr::Type(ty, starred.value.range()), x.range())
+                }
+                _ => CallArg::ty(ty, x.range()),
+            })
+            .collect::<Vec<_>>();
+        let keywords = call
+            .arguments
+            .keywords
+            .iter()
+            .zip(&keyword_types)
+            .map(|(x, ty)| CallKeyword {
+                range: x.range,
+                arg: x.arg.as_ref(),
+                value: TypeOrExpr::Type(ty, x.value.range()),
+            })
+            .collect::<Vec<_>>();
+
+        transaction.ad_hoc_solve(&handle, |solver| {
+            let error_config = ErrorConfig::new(&ErrorDisplayConfig::default(), false);
+            let mut explanation = Vec::new();
+            for candidate in candidates {
+                let errors = solver.error_collector();
+                let target = solver.as_call_target_or_error(
+                    candidate.clone(),
+                    CallStyle::FreeForm,
+                    call.range,
+                    &errors,
+                    None,
+                );
+                solver.call_infer(target, &args, &keywords, call.range, &errors, None, None);
+                let shown = match &candidate {
+                    Type::BoundMethod(method) => method.to_callable(),
+                    _ => None,
+                }
+                .unwrap_or(candidate);
+                let shown = solver.for_display(shown).to_string();
+                let errors = errors.collect(&error_config).shown;
+                if errors.is_empty() {
+                    return shown;
+                }
+                explanation.push(shown);
+                explanation.extend(errors.iter().map(|e| format!("  {}", e.msg_header())));
+            }
+            explanation.join("\n")
+        })
+    }
+
+    /// The type produced by the innermost `await` expression at the given (one-indexed) line
+    /// and column, i.e. the result of the awaitable. Returns `None` if the awaited value isn't
+    /// awaitable, in which case the check reports an `async-error` there.
+    pub fn await_result_type(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<String> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let answers = transaction.get_answers(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let range = Ast::locate_node(&ast, position)
+            .into_iter()
+            .find_map(|x| match x {
+                AnyNodeRef::ExprAwait(x) => Some(x.range),
+                _ => None,
+            })?;
+        let ty = answers.get_type_trace(range)?.arc_clone();
+        if ty.is_error() {
+            return None;
+        }
+        Some(answers.for_display(ty).to_string())
+    }
+
+    /// The source of the definition that the name at the given (one-indexed) line and column
+    /// refers to, along with the path of the module it is in. This is the whole statement that
+    /// defines the name, e.g. a `def` including its decorators and body, or an assignment.
+    /// For a parameter, it is just the parameter and its annotation.
+    /// The definition is the one go-to-definition picks, so a name imported from a module with
+    /// a stub gives the source from the stub.
+    pub fn definition_source(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<(ModulePath, String)> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let (_, definition, _) = transaction.find_definition(&handle, position, true)?;
+
+        let definition_handle = self.make_handle(
+            definition.module_info.name(),
+            definition.module_info.path().dupe(),
+        );
+        let ast = transaction
+            .get_ast(&definition_handle)
+            .unwrap_or_else(|| Arc::new(Ast::parse(definition.module_info.contents()).0));
+        let range = Ast::locate_node(&ast, definition.range.start())
+            .into_iter()
+            .find_map(|x| match x {
+                AnyNodeRef::StmtFunctionDef(_)
+                | AnyNodeRef::StmtClassDef(_)
+                | AnyNodeRef::StmtAssign(_)
+                | AnyNodeRef::StmtAnnAssign(_)
+                | AnyNodeRef::StmtAugAssign(_)
+                | AnyNodeRef::StmtTypeAlias(_)
+                | AnyNodeRef::StmtImport(_)
+                | AnyNodeRef::StmtImportFrom(_)
+                | AnyNodeRef::StmtFor(_)
+                | AnyNodeRef::StmtWith(_)
+                | AnyNodeRef::Parameter(_) => Some(x.range()),
+                _ => None,
+            })
+            .unwrap_or(definition.range);
+        Some((
+            definition.module_info.path().dupe(),
+            definition.module_info.code_at(range).to_owned(),
+        ))
+    }
+
+    /// The rendered types of the decorators on the innermost function or class definition
+    /// containing the given (one-indexed) line and column, in the order they are applied
+    /// (bottom-most first). For a decorator call like `@foo(bar)` this is the type of the call,
+    /// i.e. the decorator that actually gets applied. Returns `None` if there is no definition there.
+    pub fn get_decorators(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<Vec<String>> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let answers = transaction.get_answers(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let decorators = Ast::locate_node(&ast, position)
+            .into_iter()
+            .find_map(|x| match x {
+                AnyNodeRef::StmtFunctionDef(x) => Some(&x.decorator_list),
+                AnyNodeRef::StmtClassDef(x) => Some(&x.decorator_list),
+                _ => None,
+            })?;
+        decorators
+            .iter()
+            .rev()
+            .map(|x| {
+                let ty = answers.get_type_trace(x.expression.range())?;
+                Some(answers.for_display(ty.arc_clone()).to_string())
+            })
+            .collect()
+    }
+
+    /// Classify each name in `from target import names` as seen from the given module, returning
+    /// one result per name. As at runtime, a symbol exported by `target` takes precedence over a
+    /// submodule of the same name.
+    pub fn resolve_import(
+        &self,
+        from_module: ModuleName,
+        from_path: ModulePath,
+        target: &str,
+        names: &[&str],
+    ) -> Vec<ImportResolution> {
+        let handle = self.make_handle(from_module, from_path);
+        let transaction = self.state.transaction();
+        let target = ModuleName::from_str(target);
+        let Ok(target_handle) = transaction.import_handle(&handle, target, None) else {
+            return names.map(|_| ImportResolution::Unresolved);
+        };
+        let exports = transaction.get_exports(&target_handle);
+        names.map(|name| {
+            let name = Name::new(name);
+            if exports.contains_key(&name)
+                && let Some(ty) = transaction.ad_hoc_solve(&target_handle, |solver| {
+                    let ty = solver.get_from_module(
+                        target,
+                        Some(target_handle.path()),
+                        &KeyExport(name.clone()),
+                    );
+                    solver.for_display(ty.arc_clone()).to_string()
+                })
+            {
+                ImportResolution::Member(ty)
+            } else if let Ok(submodule) =
+                transaction.import_handle(&handle, target.append(&name), None)
+            {
+                ImportResolution::Submodule(submodule.path().dupe())
+            } else {
+                ImportResolution::Unresolved
+            }
+        })
+    }
+
+    /// The types bound by the capture patterns (`case x`, `case [*rest]`, `case {**rest}`,
+    /// `case _ as x` and so on) in the innermost `match` statement containing the given
+    /// (one-indexed) line and column, in source order. The types are those after the
+    /// pattern's narrowing, e.g. a slice of the subject tuple for a starred capture.
+    /// Returns `None` if there is no `match` statement there.
+    pub fn match_pattern_types(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<Vec<(DisplayRange, String)>> {
+        fn f<'a>(x: &'a Pattern, res: &mut Vec<&'a Identifier>) {
+            match x {
+                Pattern::MatchValue(_) | Pattern::MatchSingleton(_) => {}
+                Pattern::MatchSequence(x) => x.patterns.iter().for_each(|x| f(x, res)),
+                Pattern::MatchMapping(x) => {
+                    x.patterns.iter().for_each(|x| f(x, res));
+                    res.extend(&x.rest);
+                }
+                Pattern::MatchClass(x) => {
+                    x.arguments.patterns.iter().for_each(|x| f(x, res));
+                    x.arguments.keywords.iter().for_each(|x| f(&x.pattern, res));
+                }
+                Pattern::MatchStar(x) => res.extend(&x.name),
+                Pattern::MatchAs(x) => {
+                    if let Some(pattern) = &x.pattern {
+                        f(pattern, res);
+                    }
+                    res.extend(&x.name);
+                }
+                Pattern::MatchOr(x) => x.patterns.iter().for_each(|x| f(x, res)),
+            }
+        }
+
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let bindings = transaction.get_bindings(&handle)?;
+        let answers = transaction.get_answers(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let stmt = Ast::locate_node(&ast, position)
+            .into_iter()
+            .find_map(|x| match x {
+                AnyNodeRef::StmtMatch(x) => Some(x),
+                _ => None,
+            })?;
+        let mut captures = Vec::new();
+        for case in &stmt.cases {
+            f(&case.pattern, &mut captures);
+        }
+        captures.sort_by_key(|x| x.range.start());
+        Some(
+            captures
+                .into_iter()
+                .filter_map(|x| {
+                    let key = Key::Definition(ShortIdentifier::new(x));
+                    if !bindings.is_valid_key(&key) {
+                        return None;
+                    }
+                    let ty = answers.get_idx(bindings.key_to_idx(&key))?.arc_clone_ty();
+                    Some((
+                        module_info.display_range(x.range),
+                        answers.for_display(ty).to_string(),
+                    ))
+                })
+                .collect(),
+        )
+    }
+
+    /// The type of the variable `var` as narrowed at the given (one-indexed) line and column,
+    /// taken from the first read of `var` at or after that position in the innermost statement
+    /// containing it. So a position inside `if isinstance(x, int):` sees `x` as narrowed, while
+    /// a position on the `if` itself sees the type before the guard. The narrowed types aren't kept
+    /// for arbitrary positions, only for the expressions that read them, so this returns `None` if
+    /// `var` isn't read at or after the position within that statement, even where it is narrowed,
+    /// such as on a `pass` in the body of `if x is not None:`.
+    pub fn narrowed_type_at(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+        var: &str,
+    ) -> Option<String> {
+        fn innermost<'a>(x: &'a Stmt, position: TextSize, res: &mut Option<&'a Stmt>) {
+            if x.range().contains_inclusive(position) {
+                *res = Some(x);
+                x.recurse(&mut |x| innermost(x, position, res));
+            }
+        }
+        fn first_read<'a>(
+            x: &'a Expr,
+            var: &str,
+            position: TextSize,
+            res: &mut Option<&'a ExprName>,
+        ) {
+            if res.is_some() {
+                return;
+            }
+            if let Expr::Name(x) = x
+                && x.ctx == ExprContext::Load
+                && x.id.as_str() == var
+                && x.range.end() >= position
+            {
+                *res = Some(x);
+                return;
+            }
+            x.recurse(&mut |x| first_read(x, var, position, res));
+        }
+
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let answers = transaction.get_answers(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let mut stmt = None;
+        for x in &ast.body {
+            innermost(x, position, &mut stmt);
+        }
+        let mut read = None;
+        stmt?.visit(&mut |x| first_read(x, var, position, &mut read));
+        let ty = answers.get_type_trace(read?.range)?;
+        Some(answers.for_display(ty.arc_clone()).to_string())
+    }
+
+    /// For the innermost `super(...)` call containing the given (one-indexed) line and column,
+    /// the class that attribute lookups on it start from, i.e. the next class in the MRO.
+    /// Both the zero-argument and explicit `super(Base, self)` forms are supported. Returns `None`
+    /// if the call is invalid, such as a zero-argument `super()` outside a method.
+    pub fn super_type(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<String> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let call = Ast::locate_node(&ast, position)
+            .into_iter()
+            .find_map(|x| match x {
+                AnyNodeRef::ExprCall(x)
+                    if let Expr::Name(func) = &*x.func
+                        && func.id.as_str() == "super" =>
+                {
+                    Some(x)
+                }
+                _ => None,
+            })?;
+        let key = Key::SuperInstance(call.range);
+        let bindings = transaction.get_bindings(&handle)?;
+        if !bindings.is_valid_key(&key) {
+            return None;
+        }
+        let answers = transaction.get_answers(&handle)?;
+        match answers.get_idx(bindings.key_to_idx(&key))?.arc_clone_ty() {
+            Type::SuperInstance(box (cls, _)) => {
+                Some(answers.for_display(Type::ClassType(cls)).to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// The type bound by the `as` clause of the `with` statement at the given (one-indexed) line
+    /// and column, i.e. the result of `__enter__`, or of `__aenter__` for an `async with`. When
+    /// the statement has several items, the one under the cursor is used, or the only one when
+    /// the cursor is elsewhere in the statement. An item without an `as` clause gives `None`.
+    pub fn with_target_type(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<String> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let with = Ast::locate_node(&ast, position)
+            .into_iter()
+            .find_map(|x| match x {
+                AnyNodeRef::StmtWith(x) => Some(x),
+                _ => None,
+            })?;
+        let item = match with
+            .items
+            .iter()
+            .find(|item| item.range().contains_inclusive(position))
+        {
+            Some(item) => item,
+            None if let [item] = with.items.as_slice() => item,
+            None => return None,
+        };
+        item.optional_vars.as_ref()?;
+        let key = Key::ContextExpr(item.context_expr.range());
+        let bindings = transaction.get_bindings(&handle)?;
+        if !bindings.is_valid_key(&key) {
+            return None;
+        }
+        let answers = transaction.get_answers(&handle)?;
+        let context_manager = answers.get_idx(bindings.key_to_idx(&key))?.arc_clone_ty();
+        transaction.ad_hoc_solve(&handle, |solver| {
+            let ty = solver.context_value(
+                &context_manager,
+                IsAsync::new(with.is_async),
+                item.context_expr.range(),
+                &solver.error_collector(),
+            );
+            solver.for_display(ty).to_string()
+        })
+    }
+
+    /// The inferred element type of the innermost list, set or dict literal containing the given
+    /// (one-indexed) line and column, e.g. `int | str` for `[1, "a", 2]`. For a dict, the key and
+    /// value types are separated by a comma, e.g. `str, int`. Since the types come from inference,
+    /// an empty literal takes its type from its context, such as an annotation.
+    pub fn container_element_types(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<String> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let range = Ast::locate_node(&ast, position)
+            .into_iter()
+            .find_map(|x| match x {
+                AnyNodeRef::ExprList(x) => Some(x.range),
+                AnyNodeRef::ExprSet(x) => Some(x.range),
+                AnyNodeRef::ExprDict(x) => Some(x.range),
+                _ => None,
+            })?;
+        let answers = transaction.get_answers(&handle)?;
+        let ty = answers.get_type_trace(range)?;
+        match &*ty {
+            Type::ClassType(cls) => Some(
+                cls.targs()
+                    .as_slice()
+                    .iter()
+                    .map(|x| answers.for_display(x.clone()).to_string())
+                    .join(", "),
+            ),
+            _ => None,
+        }
+    }
+
+    /// The signature used when calling the class whose body contains the given (one-indexed)
+    /// line and column, e.g. `(x: int) -> C`. This comes from `__init__` and `__new__`, including
+    /// the ones synthesized for dataclasses and named tuples, or a metaclass `__call__`. If `__new__`
+    /// returns something other than an instance of the class, `__init__` is ignored and the
+    /// signature has that return type, as it would when the class is called.
+    pub fn constructor_signature(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<String> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let class_def = Ast::locate_node(&ast, position)
+            .into_iter()
+            .find_map(|x| match x {
+                AnyNodeRef::StmtClassDef(x) => Some(x),
+                _ => None,
+            })?;
+        let bindings = transaction.get_bindings(&handle)?;
+        let answers = transaction.get_answers(&handle)?;
+        let cls = answers
+            .get_idx(bindings.try_key_to_idx(&KeyClass(ShortIdentifier::new(&class_def.name)))?)?
+            .0
+            .clone()?;
+        transaction
+            .ad_hoc_solve(&handle, |solver| {
+                let ty = solver.constructor_to_callable(&solver.as_class_type_unchecked(&cls))?;
+                Some(solver.for_display(ty).to_string())
+            })
+            .flatten()
+    }
+
+    /// The names and types of the fields of the `NamedTuple` defined at the given (one-indexed)
+    /// line and column, in order. Both the class form and the functional form, e.g.
+    /// `P = NamedTuple("P", [("x", int)])`, are supported. Returns `None` if the innermost
+    /// class definition there isn't a named tuple.
+    pub fn namedtuple_fields(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<Vec<(String, String)>> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let class_name = Ast::locate_node(&ast, position)
+            .into_iter()
+            .find_map(|x| match x {
+                AnyNodeRef::StmtClassDef(x) => Some(ShortIdentifier::new(&x.name)),
+                AnyNodeRef::StmtAssign(x) if let [Expr::Name(target)] = x.targets.as_slice() => {
+                    Some(ShortIdentifier::expr_name(target))
+                }
+                _ => None,
+            })?;
+        let key = KeyClass(class_name);
+        let bindings = transaction.get_bindings(&handle)?;
+        if !bindings.is_valid_key(&key) {
+            return None;
+        }
+        let answers = transaction.get_answers(&handle)?;
+        let cls = answers.get_idx(bindings.key_to_idx(&key))?.0.clone()?;
+        transaction
+            .ad_hoc_solve(&handle, |solver| {
+                let elements =
+                    solver.named_tuple_elements(&solver.as_class_type_unchecked(&cls))?;
+                Some(
+                    elements.into_map(|(name, ty)| {
+                        (name.to_string(), solver.for_display(ty).to_string())
+                    }),
+                )
+            })
+            .flatten()
+    }
+
+    /// Whether `symbol`, defined at the top level of the module, is part of its public API,
+    /// i.e. brought in by `from <module> import *`. If the module defines `__all__`, that decides.
+    /// Otherwise names starting with a single underscore are private, and in stubs only local
+    /// definitions and explicit re-exports (`from x import y as y`) are public.
+    /// Returns `None` if the module isn't loaded or doesn't define `symbol`.
+    pub fn is_exported(&self, name: ModuleName, path: ModulePath, symbol: &str) -> Option<bool> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        transaction.get_module_info(&handle)?;
+        let symbol = Name::new(symbol);
+        if !transaction.get_exports(&handle).contains_key(&symbol) {
+            return None;
+        }
+        Some(transaction.get_wildcard(&handle).contains(&symbol))
+    }
+
+    /// The type parameters of the innermost class or function definition containing the given
+    /// (one-indexed) line and column, in declaration order. For a decorated function, these are
+    /// the type parameters of the function as written, before any decorators are applied.
+    pub fn type_parameters(
+        &self,
+        name: ModuleName,
+        path: ModulePath,
+        line: u32,
+        col: u32,
+    ) -> Option<Vec<TypeParamInfo>> {
+        let handle = self.make_handle(name, path);
+        let transaction = self.state.transaction();
+        let ast = transaction.get_ast(&handle)?;
+        let module_info = transaction.get_module_info(&handle)?;
+        let position = text_size(&module_info, line, col)?;
+        let bindings = transaction.get_bindings(&handle)?;
+        let answers = transaction.get_answers(&handle)?;
+        let tparams = match Ast::locate_node(&ast, position).into_iter().find(|x| {
+            matches!(
+                x,
+                AnyNodeRef::StmtClassDef(_) | AnyNodeRef::StmtFunctionDef(_)
+            )
+        })? {
+            AnyNodeRef::StmtClassDef(class_def) => {
+                let cls = answers
+                    .get_idx(
+                        bindings
+                            .try_key_to_idx(&KeyClass(ShortIdentifier::new(&class_def.name)))?,
+                    )?
+                    .0
+                    .clone()?;
+                transaction.ad_hoc_solve(&handle, |solver| solver.get_class_tparams(&cls))?
+            }
+            AnyNodeRef::StmtFunctionDef(func) => {
+                let decorated = answers.get_idx(
+                    bindings.try_key_to_idx(&KeyFunction(ShortIdentifier::new(&func.name)))?,
+                )?;
+                match decorated.decorator_steps.first().unwrap_or(&decorated.ty) {
+                    Type::Forall(box Forall { tparams, .. }) => tparams.dupe(),
+                    _ => Arc::new(TParams::default()),
+                }
+            }
+            _ => return None,
+        };
+        let display = |ty: &Type| answers.for_display(ty.clone()).to_string();
+        Some(
+            tparams
+                .iter()
+                .map(|tparam| {
+                    let (bound, constraints) = match tparam.restriction() {
+                        Restriction::Bound(bound) => (Some(display(bound)), Vec::new()),
+                        Restriction::Constraints(constraints) => (None, constraints.map(display)),
+                        Restriction::Unrestricted => (None, Vec::new()),
+                    };
+                    TypeParamInfo {
+                        name: tparam.name().to_string(),
+                        kind: tparam.quantified.kind(),
+                        bound,
+                        constraints,
+                        variance: tparam.variance,
+                        default: tparam.default().map(display),
+                    }
+                })
+                .collect(),
+        )
+    }
 }
@@ -32,11 +32,12 @@ use crate::types::callable::Function;
 use crate::types::callable::Params;
 use crate::types::module::Module;
 use crate::types::quantified::QuantifiedInfo;
+use crate::types::simplify::UnionsCache;
 use crate::types::simplify::simplify_tuples;
 use crate::types::simplify::unions;
-use crate::types::simplify::unions_with_literals;
 use crate::types::types::TParams;
 use crate::types::types::Type;
+use crate::types::types::UNRESOLVED_IMPORT_NOTE;
 use crate::types::types::Var;
 
 /// Error message when a variable has leaked from one module to another.
@@ -102,6 +103,9 @@ impl Variable {
 #[derive(Debug)]
 pub struct Solver {
     variables: RwLock<SmallMap<Var, Variable>>,
+    unions_cache: UnionsCache,
+    /// Inferred concrete tuples longer than this are widened to unbounded tuples.
+    max_concrete_tuple_length: usize,
 }
 
 impl Display for Solver {
@@ -119,12 +123,18 @@ const TYPE_LIMIT: usize = 20;
 
 impl Solver {
     /// Create a new solver.
-    pub fn new() -> Self {
+    pub fn new(max_concrete_tuple_length: usize) -> Self {
         Self {
             variables: Default::default(),
+            unions_cache: Default::default(),
+            max_concrete_tuple_length,
         }
     }
 
+    pub fn max_concrete_tuple_length(&self) -> usize {
+        self.max_concrete_tuple_length
+    }
+
     /// Force all non-recursive Vars in `vars`.
     ///
     /// TODO: deduplicate Variable-to-gradual-type logic with `force_var`.
@@ -400,12 +410,16 @@ impl Solver {
             errors.module_info().name(),
         );
         let kind = tcc.kind.as_error_kind();
+        let mut msg = vec1![msg];
+        if got.is_degraded_by_unresolved_import() || want.is_degraded_by_unresolved_import() {
+            msg.push(UNRESOLVED_IMPORT_NOTE.to_owned());
+        }
         match tcc.context {
             Some(ctx) => {
-                errors.add(loc, kind, Some(&|| ctx.clone()), vec1![msg]);
+                errors.add(loc, kind, Some(&|| ctx.clone()), msg);
             }
             None => {
-                errors.add(loc, kind, None, vec1![msg]);
+                errors.add(loc, kind, None, msg);
             }
         }
     }
@@ -450,7 +464,8 @@ impl Solver {
             })
             .collect::<Vec<_>>();
         branches.extend(modules.into_values().map(Type::Module));
-        unions_with_literals(branches, type_order.stdlib())
+        self.unions_cache
+            .unions_with_literals(branches, type_order.stdlib())
     }
 
     /// Record a variable that is used recursively.
@@ -7,7 +7,6 @@
 
 use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::iter;
 
 use itertools::EitherOrBoth;
 use itertools::Itertools;
@@ -18,7 +17,6 @@ use starlark_map::small_map::SmallMap;
 use crate::alt::answers::LookupAnswer;
 use crate::python::dunder;
 use crate::solver::solver::Subset;
-use crate::types::callable::Callable;
 use crate::types::callable::Function;
 use crate::types::callable::Param;
 use crate::types::callable::ParamList;
@@ -26,7 +24,6 @@ use crate::types::callable::Params;
 use crate::types::callable::Required;
 use crate::types::class::ClassType;
 use crate::types::quantified::QuantifiedKind;
-use crate::types::simplify::unions;
 use crate::types::tuple::Tuple;
 use crate::types::type_var::Restriction;
 use crate::types::type_var::Variance;
@@ -314,70 +311,6 @@ impl<'a, Ans: LookupAnswer> Subset<'a, Ans> {
             .and_then(|attr| self.type_order.resolve_as_instance_method(attr))
     }
 
-    fn constructor_to_callable(&mut self, cls: &ClassType) -> Option<Type> {
-        let class_type = cls.clone().to_type();
-        if let Some(mut metaclass_call_attr_ty) = self.type_order.get_metaclass_dunder_call(cls) {
-            // If the class has a custom metaclass and the return type of the metaclass's __call__
-            // is not a subclass of the current class, use that and ignore __new__ and __init__
-            if metaclass_call_attr_ty
-                .callable_return_type()
-                .is_some_and(|ret| {
-                    !self
-                        .type_order
-                        .is_compatible_constructor_return(&ret, cls.class_object())
-                })
-            {
-                return Some(metaclass_call_attr_ty);
-            }
-        }
-        // Default constructor that takes no args and returns Self.
-        let default_constructor = || {
-            Type::Callable(Box::new(Callable::list(
-                ParamList::new(Vec::new()),
-                class_type.clone(),
-            )))
-        };
-        // Check the __new__ method and whether it comes from object or has been overridden
-        let (new_attr_ty, overrides_new) = if let Some(mut t) = self
-            .type_order
-            .get_dunder_new(cls)
-            .and_then(|t| t.to_unbound_callable())
-        {
-            if t.callable_return_type().is_some_and(|ret| {
-                !self
-                    .type_order
-                    .is_compatible_constructor_return(&ret, cls.class_object())
-            }) {
-                // If the return type of __new__ is not a subclass of the current class, use that and ignore __init__
-                return Some(t);
-            }
-            (t, true)
-        } else {
-            (default_constructor(), false)
-        };
-        // Check the __init__ method and whether it comes from object or has been overridden
-        let (init_attr_ty, overrides_init) =
-            if let Some(mut t) = self.type_order.get_dunder_init(cls, false) {
-                // Replace the return type with Self (the current class)
-                t.set_callable_return_type(class_type.clone());
-                (t, true)
-            } else {
-                (default_constructor(), false)
-            };
-        if !overrides_new && overrides_init {
-            // If `__init__` is overridden and `__new__` is inherited from object, use `__init__`
-            Some(init_attr_ty)
-        } else if overrides_new && !overrides_init {
-            // If `__new__` is overridden and `__init__` is inherited from object, use `__new__`
-            Some(new_attr_ty)
-        } else {
-            let result = unions(vec![new_attr_ty, init_attr_ty]);
-            // If both are overridden, take the union
-            // Only if neither are overridden, use the `__new__` and `__init__` from object
-            Some(result)
-        }
-    }
-
     pub fn is_subset_protocol(&mut self, got: Type, protocol: ClassType) -> bool {
         let recursive_check = (got.clone(), Type::ClassType(protocol.clone()));
         if !self.recursive_assumptions.insert(recursive_check) {
@@ -425,6 +358,19 @@ impl<'a, Ans: LookupAnswer> Subset<'a, Ans> {
     }
 
     pub fn is_subset_tuple(&mut self, got: &Tuple, want: &Tuple) -> bool {
+        // Fast path for the common case of identical tuples. We skip it if there is
+        // an `Any` or a `Var` inside, since those need the full check to record
+        // the right information.
+        fn is_fully_known(x: &Tuple) -> bool {
+            let mut res = true;
+            x.visit(&mut |x: &Type| {
+                res = res && !x.any(|x| matches!(x, Type::Any(_) | Type::Var(_)))
+            });
+            res
+        }
+        if got == want && is_fully_known(got) {
+            return true;
+        }
         match (got, want) {
             (Tuple::Concrete(lelts), Tuple::Concrete(uelts)) => {
                 if lelts.len() == uelts.len() {
@@ -809,7 +755,7 @@ impl<'a, Ans: LookupAnswer> Subset<'a, Ans> {
             (
                 Type::Type(box Type::ClassType(got)),
                 Type::BoundMethod(_) | Type::Callable(_) | Type::Function(_),
-            ) if let Some(call_ty) = self.constructor_to_callable(got) => {
+            ) if let Some(call_ty) = self.type_order.constructor_to_callable(got) => {
                 self.is_subset_eq(&call_ty, want)
             }
             (Type::ClassDef(got), Type::BoundMethod(_) | Type::Callable(_) | Type::Function(_)) => {
@@ -858,44 +804,24 @@ impl<'a, Ans: LookupAnswer> Subset<'a, Ans> {
             (Type::SelfType(_), Type::SelfType(_)) => true,
             (Type::SelfType(got), _) => self.is_subset_eq(&Type::ClassType(got.clone()), want),
             (Type::Tuple(l), Type::Tuple(u)) => self.is_subset_tuple(l, u),
-            (Type::Tuple(Tuple::Concrete(left_elts)), _) => {
+            (Type::Tuple(l @ Tuple::Unpacked(box (_, middle, _))), _)
+                if !matches!(middle, Type::Tuple(_)) =>
+            {
                 let tuple_type = self
                     .type_order
                     .stdlib()
-                    .tuple(unions(left_elts.clone()))
+                    .tuple(l.as_sequence_element())
                     .to_type();
-                self.is_subset_eq(&tuple_type, want)
+                self.is_subset_eq(&tuple_type, want) && self.is_subset_eq(middle, want)
             }
-            (Type::Tuple(Tuple::Unbounded(left_elt)), _) => {
+            (Type::Tuple(l), _) => {
                 let tuple_type = self
                     .type_order
                     .stdlib()
-                    .tuple((**left_elt).clone())
+                    .tuple(l.as_sequence_element())
                     .to_type();
                 self.is_subset_eq(&tuple_type, want)
             }
-            (
-                Type::Tuple(Tuple::Unpacked(box (
-                    prefix,
-                    Type::Tuple(Tuple::Unbounded(middle)),
-                    suffix,
-                ))),
-                _,
-            ) => {
-                let elts = prefix
-                    .iter()
-                    .chain(iter::once(&**middle))
-                    .chain(suffix)
-                    .cloned()
-                    .collect::<Vec<_>>();
-                let tuple_type = self.type_order.stdlib().tuple(unions(elts)).to_type();
-                self.is_subset_eq(&tuple_type, want)
-            }
-            (Type::Tuple(Tuple::Unpacked(box (prefix, middle, suffix))), _) => {
-                let elts = prefix.iter().chain(suffix).cloned().collect::<Vec<_>>();
-                let tuple_type = self.type_order.stdlib().tuple(unions(elts)).to_type();
-                self.is_subset_eq(&tuple_type, want) && self.is_subset_eq(middle, want)
-            }
             (Type::Literal(lit), Type::LiteralString) => lit.is_string(),
             (Type::Literal(lit), t @ Type::ClassType(_)) => self.is_subset_eq(
                 &lit.general_class_type(self.type_order.stdlib())
@@ -660,7 +660,16 @@ impl<'a> Transaction<'a> {
                     self.get_type_trace(handle, range)
                 }
             }
-            None => None,
+            None => {
+                // On an `await` or `yield` keyword, show the type the whole expression evaluates to.
+                let range = match Ast::locate_node(&self.get_ast(handle)?, position).first()? {
+                    AnyNodeRef::ExprAwait(x) => x.range,
+                    AnyNodeRef::ExprYield(x) => x.range,
+                    AnyNodeRef::ExprYieldFrom(x) => x.range,
+                    _ => return None,
+                };
+                self.get_type_trace(handle, range)
+            }
         }
     }
 
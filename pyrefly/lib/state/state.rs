@@ -753,6 +753,14 @@ impl<'a> Transaction<'a> {
                     .config
                     .read()
                     .untyped_def_behavior(module_data.handle.path().as_path()),
+                implicit_optional: module_data
+                    .config
+                    .read()
+                    .implicit_optional(module_data.handle.path().as_path()),
+                max_concrete_tuple_length: module_data
+                    .config
+                    .read()
+                    .max_concrete_tuple_length(module_data.handle.path().as_path()),
             });
             {
                 let mut changed = false;
@@ -1393,6 +1401,11 @@ impl<'a> Transaction<'a> {
                     .config
                     .read()
                     .untyped_def_behavior(m.handle.path().as_path()),
+                implicit_optional: m.config.read().implicit_optional(m.handle.path().as_path()),
+                max_concrete_tuple_length: m
+                    .config
+                    .read()
+                    .max_concrete_tuple_length(m.handle.path().as_path()),
             };
             let mut step = Step::Load; // Start at AST (Load.next)
             alt.load = lock.steps.load.dupe();
@@ -1439,6 +1452,13 @@ impl<'a> Transaction<'a> {
             .exports(&self.lookup(module_data))
     }
 
+    /// The names `from <module> import *` would bring in, i.e. the module's public API.
+    pub fn get_wildcard(&self, handle: &Handle) -> Arc<SmallSet<Name>> {
+        let module_data = self.get_module(handle);
+        self.lookup_export(&module_data)
+            .wildcard(&self.lookup(module_data))
+    }
+
     pub fn get_module_docstring(&self, handle: &Handle) -> Option<DocString> {
         let module_data = self.get_module(handle);
         self.lookup_export(&module_data).docstring().cloned()
@@ -1535,6 +1555,21 @@ pub struct CommittingTransaction<'a> {
     committing_transaction_guard: MutexGuard<'a, ()>,
 }
 
+impl CommittingTransaction<'_> {
+    /// Like `Transaction::run`, but gives up as soon as `cancellation_handle` is cancelled.
+    /// A cancelled transaction has incomplete results, so must be dropped rather than committed,
+    /// which leaves the `State` exactly as it was before the transaction started.
+    pub fn run_cancellable(
+        &mut self,
+        handles: &[(Handle, Require)],
+        cancellation_handle: CancellationHandle,
+    ) -> Result<(), Cancelled> {
+        self.transaction.data.todo = TaskHeap::with_cancellation_handle(cancellation_handle);
+        self.transaction
+            .run_internal(handles, self.transaction.readable.require)
+    }
+}
+
 impl<'a> AsMut<Transaction<'a>> for CommittingTransaction<'a> {
     fn as_mut(&mut self) -> &mut Transaction<'a> {
         &mut self.transaction
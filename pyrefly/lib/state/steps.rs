@@ -18,6 +18,7 @@ use crate::alt::answers::Answers;
 use crate::alt::answers::LookupAnswer;
 use crate::alt::answers::Solutions;
 use crate::binding::bindings::Bindings;
+use crate::config::base::ImplicitOptional;
 use crate::config::base::UntypedDefBehavior;
 use crate::error::style::ErrorStyle;
 use crate::export::exports::Exports;
@@ -42,6 +43,8 @@ pub struct Context<'a, Lookup> {
     pub stdlib: &'a Stdlib,
     pub lookup: &'a Lookup,
     pub untyped_def_behavior: UntypedDefBehavior,
+    pub implicit_optional: ImplicitOptional,
+    pub max_concrete_tuple_length: usize,
 }
 
 #[derive(Debug, Default, Dupe, Clone)]
@@ -169,7 +172,7 @@ impl Step {
         ast: Arc<ModModule>,
         exports: Exports,
     ) -> Arc<(Bindings, Arc<Answers>)> {
-        let solver = Solver::new();
+        let solver = Solver::new(ctx.max_concrete_tuple_length);
         let enable_index = ctx.require.keep_index();
         let enable_trace = ctx.require.keep_answers_trace();
         let bindings = Bindings::new(
@@ -183,6 +186,7 @@ impl Step {
             ctx.uniques,
             enable_trace,
             ctx.untyped_def_behavior,
+            ctx.implicit_optional,
         );
         let answers = Answers::new(&bindings, solver, enable_index, enable_trace);
         Arc::new((bindings, Arc::new(answers)))
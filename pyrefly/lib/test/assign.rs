@@ -297,6 +297,24 @@ def f(x: list[str]):
     "#,
 );
 
+testcase!(
+    test_splat_unpacked_tuple,
+    r#"
+from typing import assert_type
+def f(x: tuple[int, *tuple[str, ...], bool], y: tuple[int, ...]):
+    a, *b, c = x
+    assert_type(a, int)
+    assert_type(b, list[str])
+    assert_type(c, bool)
+    d, *e = x
+    assert_type(d, int)
+    assert_type(e, list[str | bool])
+    g, *h = y
+    assert_type(g, int)
+    assert_type(h, list[int])
+    "#,
+);
+
 testcase!(
     test_splat_error,
     r#"
@@ -399,6 +399,19 @@ test(*(1, 2, 3, 4)) # E: Expected 3 positional arguments, got 4
 "#,
 );
 
+testcase!(
+    test_splat_tuple_variable,
+    r#"
+def test(x: int, y: str, z: int): ...
+def f(ok: tuple[int, str, int], bad: tuple[str, str, str], short: tuple[int, str], unbounded: tuple[int, ...]):
+    test(*ok)
+    test(*bad)  # E: Argument `str` is not assignable to parameter `x` with type `int` # E: Argument `str` is not assignable to parameter `z` with type `int`
+    test(*short)  # E: Missing argument `z`
+    test(*ok, 0)  # E: Expected 3 positional arguments, got 4
+    test(*unbounded)  # E: Argument `int` is not assignable to parameter `y` with type `str`
+"#,
+);
+
 testcase!(
     test_splat_iterable,
     r#"
@@ -0,0 +1,39 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use crate::config::base::ImplicitOptional;
+use crate::test::util::TestEnv;
+use crate::testcase;
+
+testcase!(
+    test_implicit_optional_error,
+    TestEnv::new_with_implicit_optional(ImplicitOptional::Error),
+    r#"
+from typing import Optional, assert_type
+def f(x: int = None):  # E: Default `None` is not assignable to parameter `x` with type `int`
+    assert_type(x, int)
+def g(x: Optional[int] = None):
+    assert_type(x, int | None)
+"#,
+);
+
+testcase!(
+    test_implicit_optional_widen,
+    TestEnv::new_with_implicit_optional(ImplicitOptional::Widen),
+    r#"
+from typing import Any, Optional, assert_type
+def f(x: int = None, *, y: str = None, z: int = 0):
+    assert_type(x, int | None)
+    assert_type(y, str | None)
+f(None, y=None)
+f(z=None)  # E: Argument `None` is not assignable to parameter `z` with type `int`
+def g(x: Optional[int] = None, y: Any = None, z: object = None):
+    assert_type(x, int | None)
+    assert_type(y, Any)
+    assert_type(z, object)
+"#,
+);
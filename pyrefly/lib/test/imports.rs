@@ -499,6 +499,16 @@ from foo import bar  # E: Could not find import of `foo`
 "#,
 );
 
+testcase!(
+    test_missing_import_degraded_union,
+    r#"
+from foo import bar  # E: Could not find import of `foo`
+def f(x: int, b: bool):
+    y = x if b else bar
+    z: str = [y]  # E: `list[int | Unknown]` is not assignable to `str`\nType is degraded by an unresolved import
+"#,
+);
+
 testcase!(
     test_missing_import_star,
     r#"
@@ -393,3 +393,40 @@ Hover Result: `BoundMethod[Foo, Overload[(self: Self@Foo, a: str) -> bool, (self
         report.trim(),
     );
 }
+
+#[test]
+fn await_and_yield_test() {
+    let code = r#"
+from typing import Generator
+async def f() -> int: ...
+async def g():
+    x = await f()
+#       ^
+def h() -> Generator[int, str, bool]:
+    y = yield 1
+#       ^
+    return True
+def k() -> Generator[int, str, None]:
+    z = yield from h()
+#       ^
+"#;
+    let report = get_batched_lsp_operations_report(&[("main", code)], get_test_report);
+    assert_eq!(
+        r#"
+# main.py
+5 |     x = await f()
+            ^
+Hover Result: `int`
+
+8 |     y = yield 1
+            ^
+Hover Result: `str`
+
+12 |     z = yield from h()
+             ^
+Hover Result: `bool`
+"#
+        .trim(),
+        report.trim(),
+    );
+}
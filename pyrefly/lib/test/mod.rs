@@ -28,6 +28,7 @@ mod enums;
 mod flow;
 mod generic_basic;
 mod generic_restrictions;
+mod implicit_optional;
 mod imports;
 mod literal;
 mod lsp;
@@ -42,6 +43,7 @@ mod paramspec;
 mod pattern_match;
 mod perf;
 mod protocol;
+mod query;
 mod returns;
 mod scope;
 mod simple;
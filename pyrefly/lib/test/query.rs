@@ -0,0 +1,1489 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use pyrefly_util::arc_id::ArcId;
+use pyrefly_util::lined_buffer::DisplayRange;
+use pyrefly_util::task_heap::CancellationHandle;
+use tempfile::TempDir;
+
+use crate::commands::config_finder::standard_config_finder;
+use crate::common::symbol_kind::SymbolKind;
+use crate::config::config::ConfigFile;
+use crate::config::config::ConfigSource;
+use crate::error::kind::ErrorKind;
+use crate::module::module_name::ModuleName;
+use crate::module::module_path::ModulePath;
+use crate::module::module_path::ModulePathDetails;
+use crate::python::sys_info::PythonPlatform;
+use crate::python::sys_info::PythonVersion;
+use crate::query::ClassMember;
+use crate::query::Coverage;
+use crate::query::ImportResolution;
+use crate::query::Query;
+use crate::query::TypeParamInfo;
+use crate::test::util::TestEnv;
+use crate::types::quantified::QuantifiedKind;
+use crate::types::type_var::PreInferenceVariance;
+
+/// A `Query` over some real files written to a temporary directory.
+struct QueryEnv {
+    query: Query,
+    files: Vec<(ModuleName, ModulePath)>,
+    _root: TempDir,
+}
+
+impl QueryEnv {
+    fn new(files: &[(&str, &str)]) -> Self {
+        let env = Self::new_unloaded(files);
+        env.query.add_files(env.files.clone());
+        env
+    }
+
+    /// Like `new`, but without adding the files to the `Query`.
+    fn new_unloaded(files: &[(&str, &str)]) -> Self {
+        let root = TempDir::new().unwrap();
+        let mut env = TestEnv::new();
+        let mut paths = Vec::new();
+        for (name, code) in files {
+            let path = root.path().join(format!("{}.py", name.replace('.', "/")));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, code).unwrap();
+            env.add_real_path(name, path.clone());
+            paths.push((ModuleName::from_str(name), ModulePath::filesystem(path)));
+        }
+        let query = Query::new(env.config_finder());
+        Self {
+            query,
+            files: paths,
+            _root: root,
+        }
+    }
+
+    fn file(&self, name: &str) -> (ModuleName, ModulePath) {
+        self.files
+            .iter()
+            .find(|(x, _)| x.as_str() == name)
+            .cloned()
+            .unwrap()
+    }
+}
+
+#[test]
+fn test_unresolved_imports() {
+    let env = QueryEnv::new(&[
+        ("foo", "x = 1"),
+        (
+            "main",
+            r#"
+import foo
+import does_not_exist
+from also_missing import y
+try:
+    import optional_dep
+except ImportError:
+    pass
+"#,
+        ),
+    ]);
+    let (name, path) = env.file("main");
+    let unresolved = |xs: Vec<(ModuleName, DisplayRange)>| {
+        xs.into_iter().map(|x| x.0.to_string()).collect::<Vec<_>>()
+    };
+    assert_eq!(
+        unresolved(env.query.unresolved_imports(name, path.clone())),
+        vec!["does_not_exist", "also_missing"]
+    );
+    assert_eq!(
+        unresolved(env.query.unresolved_imports_including_guarded(name, path)),
+        vec!["does_not_exist", "also_missing", "optional_dep"]
+    );
+}
+
+#[test]
+fn test_find_module() {
+    let env = QueryEnv::new(&[("foo.bar", "x = 1")]);
+    assert_eq!(
+        env.query.find_module(ModuleName::from_str("foo.bar")),
+        Some(env.file("foo.bar").1)
+    );
+    assert!(matches!(
+        env.query
+            .find_module(ModuleName::typing())
+            .as_ref()
+            .map(|x| x.details()),
+        Some(ModulePathDetails::BundledTypeshed(_))
+    ));
+    assert_eq!(
+        env.query
+            .find_module(ModuleName::from_str("does_not_exist")),
+        None
+    );
+}
+
+#[test]
+fn test_annotate_source() {
+    let code = r#"
+x = 1
+def f(y: int) -> str:
+    return str(y)
+z = f(
+    x,
+)
+"#;
+    let env = QueryEnv::new(&[("main", code)]);
+    let (name, path) = env.file("main");
+    let annotated = env.query.annotate_source(name, path).unwrap();
+    let lines = annotated.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), code.lines().count());
+    assert_eq!(lines[1], "x = 1  # type: Literal[1]");
+    assert_eq!(lines[5], "    x,  # type: Literal[1]");
+    assert_eq!(lines[6], ")  # type: str");
+    assert!(annotated.ends_with('\n'));
+}
+
+#[test]
+fn test_ignore_comments() {
+    let code = r#"
+def f() -> int:
+    return "x"  # type: ignore[bad-return]
+
+x: int = "y"  # type: ignore
+
+y: int = "z"  # type: ignore[bad-return]
+"#;
+    let env = QueryEnv::new(&[("main", code)]);
+    let (name, path) = env.file("main");
+    let ignores = env.query.ignore_comments(name, path);
+    let summary = ignores
+        .iter()
+        .map(|x| (x.line.get(), x.codes.clone(), x.suppressed.clone()))
+        .collect::<Vec<_>>();
+    let s = |x: &str| x.to_owned();
+    assert_eq!(
+        summary,
+        vec![
+            (3, vec![(s("bad-return"), true)], vec![s("bad-return")]),
+            (5, vec![], vec![s("bad-assignment")]),
+            (7, vec![(s("bad-return"), false)], vec![s("bad-assignment")]),
+        ]
+    );
+}
+
+#[test]
+fn test_add_files_cancelled() {
+    let env = QueryEnv::new_unloaded(&[("main", "x: int = 1")]);
+    let (name, path) = env.file("main");
+    let cancellation_handle = CancellationHandle::new();
+    cancellation_handle.cancel();
+    assert!(
+        env.query
+            .add_files_cancellable(env.files.clone(), cancellation_handle)
+            .is_err()
+    );
+    // Nothing was committed.
+    assert!(env.query.get_types_in_file(name, path.clone()).is_none());
+    // And the state is still usable.
+    assert!(
+        env.query
+            .add_files_cancellable(env.files.clone(), CancellationHandle::new())
+            .unwrap()
+            .is_empty()
+    );
+    assert!(env.query.get_types_in_file(name, path).is_some());
+}
+
+#[test]
+fn test_is_type_checking_only() {
+    let env = QueryEnv::new(&[
+        ("foo", "class A: ...\nclass B: ..."),
+        (
+            "main",
+            r#"
+from typing import TYPE_CHECKING
+if TYPE_CHECKING:
+    from foo import A, B
+else:
+    B = object
+def f(x: "A", y: B) -> None:
+    print(x)
+"#,
+        ),
+    ]);
+    let (name, path) = env.file("main");
+    let check = |line, col| {
+        env.query
+            .is_type_checking_only(name, path.clone(), line, col)
+    };
+    // `A` is only imported under `TYPE_CHECKING`.
+    assert_eq!(check(4, 21), Some(true));
+    // `B` is also bound at runtime.
+    assert_eq!(check(7, 18), Some(false));
+    // `x` is a parameter, and `print` is a builtin.
+    assert_eq!(check(8, 11), Some(false));
+    assert_eq!(check(8, 5), Some(false));
+    // There is no name on an empty line.
+    assert_eq!(check(1, 1), None);
+}
+
+#[test]
+fn test_effective_config() {
+    let root = TempDir::new().unwrap();
+    fs::write(
+        root.path().join("pyproject.toml"),
+        r#"
+[tool.pyrefly]
+python-version = "3.9.1"
+python-platform = "darwin"
+search-path = ["src"]
+site-package-path = []
+"#,
+    )
+    .unwrap();
+    let file = root.path().join("src/pkg/main.py");
+    fs::create_dir_all(file.parent().unwrap()).unwrap();
+    fs::write(&file, "").unwrap();
+
+    let query = Query::new(standard_config_finder(Arc::new(
+        |_, mut config: ConfigFile| {
+            config.configure();
+            (ArcId::new(config), Vec::new())
+        },
+    )));
+    let summary = query.effective_config(ModulePath::filesystem(file));
+    assert_eq!(
+        summary.source,
+        ConfigSource::File(root.path().join("pyproject.toml"))
+    );
+    assert!(summary.search_path.contains(&root.path().join("src")));
+    assert_eq!(summary.site_package_path, Vec::<PathBuf>::new());
+    assert_eq!(summary.python_version, PythonVersion::new(3, 9, 1));
+    assert_eq!(summary.python_platform, PythonPlatform::mac());
+
+    // Files outside any project get a synthesized default config.
+    let elsewhere = TempDir::new().unwrap();
+    let summary = query.effective_config(ModulePath::filesystem(elsewhere.path().join("x.py")));
+    assert_eq!(summary.source, ConfigSource::Synthetic);
+}
+
+#[test]
+fn test_enclosing_return_type() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+def outer(x: int) -> str:
+    def inner(y: bytes):
+        return y
+    return str(x)
+z = 1
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| {
+        env.query
+            .enclosing_return_type(name, path.clone(), line, col)
+    };
+    assert_eq!(check(5, 5), Some("str".to_owned()));
+    // The innermost function wins.
+    assert_eq!(check(4, 9), Some("bytes".to_owned()));
+    assert_eq!(check(6, 1), None);
+}
+
+#[test]
+fn test_decorator_effects() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+from typing import Callable
+def wrap(f: Callable[[int], int]) -> Callable[[str], str]: ...
+def count(f: Callable[[str], str]) -> int: ...
+@count
+@wrap
+def f(x: int) -> int:
+    return x
+class C:
+    @property
+    def p(self) -> int:
+        return 1
+def g() -> None:
+    pass
+import sys
+if sys.version_info < (3, 0):
+    def old() -> None: ...
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| env.query.decorator_effects(name, path.clone(), line, col);
+    assert_eq!(
+        check(8, 5),
+        Some(vec![
+            "(x: int) -> int".to_owned(),
+            "(str) -> str".to_owned(),
+            "int".to_owned(),
+        ])
+    );
+    assert_eq!(check(12, 9), Some(vec!["(self: Self@C) -> int".to_owned()]));
+    assert_eq!(check(14, 5), Some(vec!["() -> None".to_owned()]));
+    assert_eq!(check(1, 1), None);
+    // A function in a statically false branch has no bindings.
+    assert_eq!(check(17, 9), None);
+}
+
+#[test]
+fn test_parameter_types() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+def f(x: int, y: str = "") -> None:
+    pass
+def g(x: int, *args: str, **kwargs: bytes) -> None:
+    pass
+f(1)
+g(1, "a")
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| {
+        env.query
+            .parameter_types(name, path.clone(), line, col)
+            .map(|xs| {
+                xs.into_iter()
+                    .map(|(name, ty)| format!("{name}: {ty}"))
+                    .collect::<Vec<_>>()
+            })
+    };
+    let f = Some(vec!["x: int".to_owned(), "y: str".to_owned()]);
+    let g = Some(vec![
+        "x: int".to_owned(),
+        "args: tuple[str, ...]".to_owned(),
+        "kwargs: dict[str, bytes]".to_owned(),
+    ]);
+    // At the definitions.
+    assert_eq!(check(3, 5), f);
+    assert_eq!(check(5, 5), g);
+    // At the calls.
+    assert_eq!(check(6, 3), f);
+    assert_eq!(check(7, 3), g);
+    assert_eq!(check(1, 1), None);
+}
+
+#[test]
+fn test_check_expr_against() {
+    let env = QueryEnv::new(&[("main", "x: int = 1\ny = x + 1")]);
+    let (name, path) = env.file("main");
+    let check = |expr, expected| {
+        env.query
+            .check_expr_against(name, path.clone(), expr, expected)
+    };
+    assert_eq!(check("x", "int"), Ok(()));
+    assert_eq!(check("[x, y]", "list[int]"), Ok(()));
+    let err = check("x", "str").unwrap_err();
+    assert!(err.contains("`int` is not assignable to `str`"), "{err}");
+    let err = check("z", "int").unwrap_err();
+    assert!(err.contains("Could not find name `z`"), "{err}");
+    // The committed state is unaffected.
+    assert_eq!(env.query.iter_errors().count(), 0);
+}
+
+#[test]
+fn test_class_members() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+from typing import ClassVar
+class A:
+    def inherited(self) -> int: ...
+    def overridden(self) -> int: ...
+class B(A):
+    x: int = 1
+    y: ClassVar[str] = ""
+    def m(self, a: int) -> str: ...
+    @property
+    def p(self) -> bytes: ...
+    def overridden(self) -> int: ...
+import sys
+if sys.version_info < (3, 0):
+    class Old:
+        x: int = 1
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let member = |name: &str, ty: &str, kind, defining_class: &str| ClassMember {
+        name: name.to_owned(),
+        ty: ty.to_owned(),
+        kind,
+        defining_class: defining_class.to_owned(),
+    };
+    assert_eq!(
+        env.query.class_members(name, path.clone(), 6, 7),
+        Some(vec![
+            member("x", "int", SymbolKind::Attribute, "B"),
+            member("y", "str", SymbolKind::Variable, "B"),
+            member("m", "(a: int) -> str", SymbolKind::Method, "B"),
+            member("p", "bytes", SymbolKind::Property, "B"),
+            member("overridden", "() -> int", SymbolKind::Method, "B"),
+            member("inherited", "() -> int", SymbolKind::Method, "A"),
+        ])
+    );
+    assert_eq!(env.query.class_members(name, path.clone(), 2, 1), None);
+    // A class in a statically false branch has no bindings.
+    assert_eq!(env.query.class_members(name, path, 16, 9), None);
+}
+
+#[test]
+fn test_get_mro() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+class A: pass
+class B(A): pass
+class C(A): pass
+class D(B, C):
+    x = 1
+class T(tuple[int, ...]): pass
+class X(A, B): pass
+import sys
+if sys.version_info < (3, 0):
+    class Old: pass
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| env.query.get_mro(name, path.clone(), line, col);
+    assert_eq!(
+        check(6, 5),
+        Some(vec![
+            "D".to_owned(),
+            "B".to_owned(),
+            "C".to_owned(),
+            "A".to_owned(),
+            "object".to_owned(),
+        ])
+    );
+    let mro = check(7, 1).unwrap();
+    assert_eq!(mro[..2], ["T".to_owned(), "tuple[int, ...]".to_owned()]);
+    // An inconsistent MRO stops early, and reports an error.
+    assert_eq!(check(8, 1), Some(vec!["X".to_owned(), "object".to_owned()]));
+    assert_eq!(
+        env.query
+            .errors_for_rule(ErrorKind::InvalidInheritance)
+            .len(),
+        1
+    );
+    assert_eq!(check(1, 1), None);
+    // The binder skips statically false branches, so there is no class to look up.
+    assert_eq!(check(11, 11), None);
+}
+
+#[test]
+fn test_keyword_arg_type() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+def f(x: int, *, y: str, **kwargs: bytes) -> None: ...
+f(1, y="a", z=b"b")
+f(x=1, y=str(1), z=b"")
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| env.query.keyword_arg_type(name, path.clone(), line, col);
+    assert_eq!(check(3, 6), Some("str".to_owned()));
+    // Absorbed by `**kwargs`.
+    assert_eq!(check(3, 13), Some("bytes".to_owned()));
+    assert_eq!(check(4, 3), Some("int".to_owned()));
+    // Inside a call nested in the keyword's value.
+    assert_eq!(check(4, 14), Some("str".to_owned()));
+    // Not in a keyword argument.
+    assert_eq!(check(3, 3), None);
+}
+
+#[test]
+fn test_enclosing_self_type() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+class C:
+    def method(self):
+        pass
+    @classmethod
+    def class_method(cls):
+        pass
+    @staticmethod
+    def static_method(x: int):
+        pass
+def f(x: int):
+    pass
+import sys
+if sys.version_info < (3, 0):
+    class Old:
+        def method(self):
+            pass
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| env.query.enclosing_self_type(name, path.clone(), line, col);
+    assert_eq!(check(4, 9), Some("Self@C".to_owned()));
+    assert_eq!(check(7, 9), Some("type[Self@C]".to_owned()));
+    assert_eq!(check(10, 9), None);
+    assert_eq!(check(12, 5), None);
+    // A method in a statically false branch has no bindings.
+    assert_eq!(check(17, 13), None);
+}
+
+#[test]
+fn test_reveal_types() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+from typing import reveal_type, reveal_type as rt
+x: int = 1
+reveal_type(x)
+rt("a")  # type: ignore
+y: str = 1
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let res = env
+        .query
+        .reveal_types(name, path)
+        .into_iter()
+        .map(|(range, ty)| (range.to_string(), ty))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        res,
+        vec![
+            ("4:1-15".to_owned(), "int".to_owned()),
+            ("5:1-8".to_owned(), "Literal['a']".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn test_iter_errors() {
+    let env = QueryEnv::new(&[
+        ("a", "x: int = 'a'"),
+        ("b", "import a\ny: str = a.x"),
+        ("c", "z = 1"),
+    ]);
+    let errors = env.query.iter_errors().collect::<Vec<_>>();
+    let summary = errors
+        .iter()
+        .map(|e| {
+            (
+                env.files
+                    .iter()
+                    .find(|x| x.1 == e.path)
+                    .unwrap()
+                    .0
+                    .to_string(),
+                e.range.to_string(),
+                e.code.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            ("a".to_owned(), "1:10-13".to_owned(), "bad-assignment"),
+            ("b".to_owned(), "2:10-13".to_owned(), "bad-assignment"),
+        ]
+    );
+    // The iterator is lazy, and can be abandoned part way through.
+    assert_eq!(env.query.iter_errors().next(), errors.first().cloned());
+}
+
+#[test]
+fn test_add_files_sorted() {
+    let env = QueryEnv::new_unloaded(&[("b", "x: int = ''\ny: str = 1\n"), ("a", "z: int = ''\n")]);
+    let module = |e: &str| {
+        env.files
+            .iter()
+            .find(|(_, path)| e.contains(&path.to_string()))
+            .unwrap()
+            .0
+            .to_string()
+    };
+    let errors = env.query.add_files(env.files.clone());
+    assert_eq!(
+        errors.iter().map(|e| module(e)).collect::<Vec<_>>(),
+        vec!["a", "b", "b"]
+    );
+    assert!(errors[1].contains(":1:10-12: "));
+    assert!(errors[2].contains(":2:10-11: "));
+    // The order doesn't depend on the order the files are given in.
+    assert_eq!(
+        env.query
+            .add_files(env.files.iter().rev().cloned().collect()),
+        errors
+    );
+}
+
+#[test]
+fn test_errors_under_version() {
+    let env = QueryEnv::new(&[("a", "from typing import Self\n")]);
+    let errors = env
+        .query
+        .errors_under_version(env.files.clone(), PythonVersion::new(3, 8, 0));
+    assert_eq!(
+        errors
+            .iter()
+            .map(|e| (e.range.to_string(), e.code.as_str()))
+            .collect::<Vec<_>>(),
+        vec![("1:20-24".to_owned(), "missing-module-attribute")]
+    );
+    assert!(
+        env.query
+            .errors_under_version(env.files.clone(), PythonVersion::new(3, 11, 0))
+            .is_empty()
+    );
+    // The committed state, checked with the default version, is unaffected.
+    assert_eq!(env.query.iter_errors().next(), None);
+}
+
+#[test]
+fn test_errors_for_rule() {
+    let env = QueryEnv::new(&[
+        ("a", "x: int = 'a'\nimport does_not_exist"),
+        ("b", "y: str = 1"),
+    ]);
+    let summary = |kind| {
+        env.query
+            .errors_for_rule(kind)
+            .into_iter()
+            .map(|e| (e.range.to_string(), e.code))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        summary(ErrorKind::BadAssignment),
+        vec![
+            ("1:10-13".to_owned(), "bad-assignment".to_owned()),
+            ("1:10-11".to_owned(), "bad-assignment".to_owned()),
+        ]
+    );
+    assert_eq!(
+        summary(ErrorKind::ImportError),
+        vec![("2:8-22".to_owned(), "import-error".to_owned())]
+    );
+    assert_eq!(summary(ErrorKind::BadReturn), Vec::new());
+}
+
+#[test]
+fn test_symbol_kind() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+class C:
+    def method(self, x: int) -> int:
+        return x
+def f(y: int) -> int:
+    return C().method(y)
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| env.query.symbol_kind(name, path.clone(), line, col);
+    assert_eq!(check(2, 7), Some(SymbolKind::Class));
+    assert_eq!(check(3, 9), Some(SymbolKind::Method));
+    assert_eq!(check(5, 5), Some(SymbolKind::Function));
+    assert_eq!(check(5, 7), Some(SymbolKind::Parameter));
+    assert_eq!(check(6, 12), Some(SymbolKind::Class));
+    assert_eq!(check(6, 16), Some(SymbolKind::Method));
+    assert_eq!(check(6, 23), Some(SymbolKind::Parameter));
+}
+
+#[test]
+fn test_get_type_aliases() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+from typing import TypeAlias
+IntOrStr: TypeAlias = int | str
+type Pair = tuple[int, int]
+NotAnAlias = int
+class Tree: pass
+T: TypeAlias = Tree | list["T"]
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    assert_eq!(
+        env.query.get_type_aliases(name, path),
+        vec![
+            ("IntOrStr".to_owned(), "int | str".to_owned()),
+            ("Pair".to_owned(), "tuple[int, int]".to_owned()),
+            // The self-reference is shown by name, without touching `Tree`, which starts with
+            // the same letter.
+            ("T".to_owned(), "Tree | list[T]".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn test_unused_imports() {
+    let env = QueryEnv::new(&[
+        ("foo", "x = 1\ny = 2\nz = 3"),
+        (
+            "main",
+            r#"
+import foo
+from foo import x, y, z
+__all__ = ["y"]
+def f() -> None:
+    print(foo, z)
+"#,
+        ),
+    ]);
+    let (name, path) = env.file("main");
+    let res = env
+        .query
+        .unused_imports(name, path)
+        .into_iter()
+        .map(|(module, range)| (module, range.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        res,
+        vec![(ModuleName::from_str("foo"), "3:17-18".to_owned())]
+    );
+}
+
+#[test]
+fn test_call_edges() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+class C:
+    def m(self) -> None: ...
+def f() -> None: ...
+f()
+C().m()
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let res = env
+        .query
+        .call_edges(name, path.clone())
+        .into_iter()
+        .map(|(call, callee)| {
+            (
+                call.to_string(),
+                callee.map(|(callee_path, range)| {
+                    assert_eq!(callee_path, path);
+                    range.to_string()
+                }),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        res,
+        vec![
+            ("5:1-4".to_owned(), Some("4:5-6".to_owned())),
+            ("6:1-8".to_owned(), Some("3:9-10".to_owned())),
+            ("6:1-4".to_owned(), Some("2:7-8".to_owned())),
+        ]
+    );
+}
+
+#[test]
+fn test_is_assignable() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+x: int = 1
+y: str = ""
+z: object = None
+# Not an expression
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |from, to| env.query.is_assignable(name, path.clone(), from, to);
+    assert_eq!(check((2, 1), (4, 1)), Some(true));
+    assert_eq!(check((3, 1), (2, 1)), Some(false));
+    assert_eq!(check((5, 3), (2, 1)), None);
+}
+
+#[test]
+fn test_get_overloads() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+from typing import overload
+@overload
+def f(x: int) -> int: ...
+@overload
+def f(x: str) -> str: ...
+def f(x: int | str) -> int | str:
+    return x
+def g(x: int) -> int:
+    return x
+f(1)
+import sys
+if sys.version_info < (3, 0):
+    def old() -> None: ...
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| env.query.get_overloads(name, path.clone(), line, col);
+    let overloads = Some(vec![
+        "(x: int) -> int".to_owned(),
+        "(x: str) -> str".to_owned(),
+    ]);
+    assert_eq!(check(4, 5), overloads);
+    assert_eq!(check(11, 1), overloads);
+    assert_eq!(check(9, 5), Some(vec!["(x: int) -> int".to_owned()]));
+    // A function in a statically false branch has no bindings.
+    assert_eq!(check(14, 9), None);
+}
+
+#[test]
+fn test_explain_call() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+from typing import overload
+@overload
+def f(x: int) -> int: ...
+@overload
+def f(x: str) -> str: ...
+def f(x: int | str) -> int | str:
+    return x
+f(b"")
+f("")
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| env.query.explain_call(name, path.clone(), line, col);
+    assert_eq!(
+        check(9, 1).unwrap(),
+        [
+            "(x: int) -> int",
+            "  Argument `Literal[b'']` is not assignable to parameter `x` with type `int` in function `f`",
+            "(x: str) -> str",
+            "  Argument `Literal[b'']` is not assignable to parameter `x` with type `str` in function `f`",
+        ]
+        .join("\n")
+    );
+    // A successful call gives the matching overload.
+    assert_eq!(check(10, 3), Some("(x: str) -> str".to_owned()));
+    assert_eq!(check(2, 1), None);
+}
+
+#[test]
+fn test_comprehension_element_type() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+def f(xs: list[int]) -> None:
+    a = [str(x) for x in xs]
+    b = {str(x): x for x in xs}
+    c = [[y for y in range(x)] for x in xs]
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| {
+        env.query
+            .comprehension_element_type(name, path.clone(), line, col)
+    };
+    assert_eq!(check(3, 10), Some("str".to_owned()));
+    assert_eq!(check(4, 10), Some("str, int".to_owned()));
+    assert_eq!(check(5, 11), Some("int".to_owned()));
+    assert_eq!(check(5, 9), Some("list[int]".to_owned()));
+    assert_eq!(check(2, 1), None);
+}
+
+#[test]
+fn test_await_result_type() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+async def f() -> int: ...
+async def g() -> None:
+    x = await f()
+    y = await 1
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| env.query.await_result_type(name, path.clone(), line, col);
+    assert_eq!(check(4, 9), Some("int".to_owned()));
+    // Anywhere inside the `await` expression works.
+    assert_eq!(check(4, 15), Some("int".to_owned()));
+    assert_eq!(check(5, 9), None);
+    assert_eq!(check(2, 1), None);
+}
+
+#[test]
+fn test_definition_source() {
+    let env = QueryEnv::new(&[
+        (
+            "a",
+            r#"
+from typing import Callable
+def deco(f: Callable[[int], int]) -> Callable[[int], int]:
+    return f
+@deco
+def f(x: int) -> int:
+    return x
+"#,
+        ),
+        (
+            "b",
+            r#"
+from a import f
+y: int = f(1)
+def g(p: str) -> str:
+    return p + str(y)
+"#,
+        ),
+    ]);
+    let (name, path) = env.file("b");
+    let (a_name, a_path) = env.file("a");
+    let check = |line, col| env.query.definition_source(name, path.clone(), line, col);
+    assert_eq!(
+        check(3, 10),
+        Some((
+            a_path.clone(),
+            "@deco\ndef f(x: int) -> int:\n    return x".to_owned()
+        ))
+    );
+    assert_eq!(
+        check(5, 20),
+        Some((path.clone(), "y: int = f(1)".to_owned()))
+    );
+    assert_eq!(check(5, 12), Some((path.clone(), "p: str".to_owned())));
+    assert_eq!(
+        env.query.definition_source(a_name, a_path.clone(), 6, 5),
+        Some((
+            a_path,
+            "@deco\ndef f(x: int) -> int:\n    return x".to_owned()
+        ))
+    );
+}
+
+#[test]
+fn test_type_diff() {
+    let old = r#"
+def f(x: int | None) -> None:
+    y = x
+    z = str(y)
+"#;
+    let new = r#"
+def f(x: int | None) -> None:
+    # A new comment shifts everything below it.
+    if x is None:
+        return
+    y = x
+    z = str(y)
+"#;
+    let env = QueryEnv::new(&[("main", old)]);
+    let (name, path) = env.file("main");
+    let diff = env
+        .query
+        .type_diff(name, path.clone(), new.to_owned())
+        .into_iter()
+        .map(|(range, old, new)| (range.to_string(), old, new))
+        .collect::<Vec<_>>();
+    // The `x` in `y = x` pairs with the same `x` after the edit, not the new `x` in `x is None`,
+    // so both it and the `y` in `str(y)` are seen to be narrowed.
+    assert_eq!(
+        diff,
+        vec![
+            (
+                "6:9-10".to_owned(),
+                "int | None".to_owned(),
+                "int".to_owned()
+            ),
+            (
+                "7:13-14".to_owned(),
+                "int | None".to_owned(),
+                "int".to_owned()
+            ),
+        ]
+    );
+    // The committed file is unaffected.
+    assert_eq!(env.query.type_diff(name, path, old.to_owned()), Vec::new());
+}
+
+#[test]
+fn test_get_decorators() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+class Marker:
+    def __call__(self, f: object) -> object:
+        return f
+def marker(tag: str) -> Marker:
+    return Marker()
+def plain(f: object) -> object:
+    return f
+@plain
+@marker("x")
+def f() -> None:
+    pass
+def g() -> None:
+    pass
+x = 1
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| env.query.get_decorators(name, path.clone(), line, col);
+    let expected = Some(vec![
+        "Marker".to_owned(),
+        "(f: object) -> object".to_owned(),
+    ]);
+    assert_eq!(check(11, 5), expected);
+    assert_eq!(check(12, 5), expected);
+    assert_eq!(check(13, 5), Some(Vec::new()));
+    assert_eq!(check(15, 1), None);
+}
+
+#[test]
+fn test_resolve_import() {
+    let env = QueryEnv::new(&[
+        ("pkg", "x: int = 1\nboth: str = ''\n"),
+        ("pkg.sub", ""),
+        ("pkg.both", ""),
+        ("main", "from pkg import x, sub, both\n"),
+    ]);
+    let (name, path) = env.file("main");
+    let (_, sub_path) = env.file("pkg.sub");
+    assert_eq!(
+        env.query
+            .resolve_import(name, path.clone(), "pkg", &["x", "sub", "both", "missing"]),
+        vec![
+            ImportResolution::Member("int".to_owned()),
+            ImportResolution::Submodule(sub_path),
+            // The symbol shadows the submodule of the same name.
+            ImportResolution::Member("str".to_owned()),
+            ImportResolution::Unresolved,
+        ]
+    );
+    assert_eq!(
+        env.query.resolve_import(name, path, "nowhere", &["x"]),
+        vec![ImportResolution::Unresolved]
+    );
+}
+
+#[test]
+fn test_match_pattern_types() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+class Point:
+    x: int
+    y: str
+def f(p: Point) -> None:
+    match p:
+        case Point(x=a, y=b):
+            pass
+def g(t: tuple[int, str, bytes]) -> None:
+    match t:
+        case (c, *rest):
+            pass
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| {
+        env.query
+            .match_pattern_types(name, path.clone(), line, col)
+            .map(|xs| {
+                xs.into_iter()
+                    .map(|(range, ty)| (range.to_string(), ty))
+                    .collect::<Vec<_>>()
+            })
+    };
+    assert_eq!(
+        check(6, 5),
+        Some(vec![
+            ("7:22-23".to_owned(), "int".to_owned()),
+            ("7:27-28".to_owned(), "str".to_owned()),
+        ])
+    );
+    // The starred capture gets the rest of the tuple.
+    assert_eq!(
+        check(12, 13),
+        Some(vec![
+            ("11:15-16".to_owned(), "int".to_owned()),
+            ("11:19-23".to_owned(), "list[str | bytes]".to_owned()),
+        ])
+    );
+    assert_eq!(check(5, 5), None);
+}
+
+#[test]
+fn test_narrowed_type_at() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+def f(x: int | str) -> None:
+    if isinstance(x, int):
+        y = x
+    z = x
+def g(x: int | None) -> None:
+    if x is not None:
+        pass
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col, var| {
+        env.query
+            .narrowed_type_at(name, path.clone(), line, col, var)
+    };
+    // On the guard itself, `x` hasn't been narrowed yet.
+    assert_eq!(check(3, 5, "x"), Some("int | str".to_owned()));
+    assert_eq!(check(4, 9, "x"), Some("int".to_owned()));
+    assert_eq!(check(5, 5, "x"), Some("int | str".to_owned()));
+    assert_eq!(check(4, 9, "z"), None);
+    // Narrowed, but with no later read of `x` to take the type from.
+    assert_eq!(check(8, 9, "x"), None);
+}
+
+#[test]
+fn test_super_type() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+class A:
+    def f(self) -> None: ...
+class B(A):
+    def f(self) -> None: ...
+class C(B):
+    def f(self) -> None:
+        super().f()
+        super(B, self).f()
+super()
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| env.query.super_type(name, path.clone(), line, col);
+    assert_eq!(check(8, 10), Some("B".to_owned()));
+    assert_eq!(check(9, 10), Some("A".to_owned()));
+    assert_eq!(check(10, 3), None);
+    assert_eq!(check(8, 17), None);
+}
+
+#[test]
+fn test_with_target_type() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+class Sync:
+    def __enter__(self) -> int: ...
+    def __exit__(self, *args) -> None: ...
+class Async:
+    async def __aenter__(self) -> str: ...
+    async def __aexit__(self, *args) -> None: ...
+with Sync() as x:
+    pass
+async def f():
+    async with Async() as y:
+        pass
+with Sync() as a, Sync(), Sync() as b:
+    pass
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| env.query.with_target_type(name, path.clone(), line, col);
+    assert_eq!(check(8, 16), Some("int".to_owned()));
+    assert_eq!(check(9, 5), Some("int".to_owned()));
+    assert_eq!(check(11, 27), Some("str".to_owned()));
+    assert_eq!(check(13, 7), Some("int".to_owned()));
+    assert_eq!(check(13, 20), None);
+    assert_eq!(check(13, 37), Some("int".to_owned()));
+    assert_eq!(check(13, 2), None);
+}
+
+#[test]
+fn test_container_element_types() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+x = [1, "a", 2]
+y: set[int] = set()
+z: list[str] = []
+d = {"a": [1], "b": [2]}
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| {
+        env.query
+            .container_element_types(name, path.clone(), line, col)
+    };
+    assert_eq!(check(2, 6), Some("int | str".to_owned()));
+    assert_eq!(check(4, 16), Some("str".to_owned()));
+    assert_eq!(check(5, 5), Some("str, list[int]".to_owned()));
+    assert_eq!(check(5, 11), Some("int".to_owned()));
+    assert_eq!(check(3, 1), None);
+}
+
+#[test]
+fn test_constructor_signature() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+from dataclasses import dataclass
+@dataclass
+class D:
+    x: int
+    y: str = ""
+class C:
+    def __init__(self, x: int) -> None:
+        pass
+class E:
+    pass
+import sys
+if sys.version_info < (3, 0):
+    class Old: pass
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| {
+        env.query
+            .constructor_signature(name, path.clone(), line, col)
+    };
+    assert_eq!(check(5, 5), Some("(x: int, y: str = ...) -> D".to_owned()));
+    assert_eq!(check(8, 9), Some("(x: int) -> C".to_owned()));
+    assert_eq!(check(11, 5), Some("() -> E".to_owned()));
+    assert_eq!(check(2, 1), None);
+    // A class in a statically false branch has no bindings.
+    assert_eq!(check(14, 11), None);
+}
+
+#[test]
+fn test_namedtuple_fields() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+from typing import NamedTuple
+class Point(NamedTuple):
+    x: int
+    y: str = ""
+    def norm(self) -> int: ...
+P = NamedTuple("P", [("x", int), ("y", list[str])])
+class NotATuple:
+    x: int
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| env.query.namedtuple_fields(name, path.clone(), line, col);
+    let fields = |xs: &[(&str, &str)]| {
+        Some(
+            xs.iter()
+                .map(|(name, ty)| (name.to_string(), ty.to_string()))
+                .collect::<Vec<_>>(),
+        )
+    };
+    assert_eq!(check(4, 5), fields(&[("x", "int"), ("y", "str")]));
+    assert_eq!(check(7, 1), fields(&[("x", "int"), ("y", "list[str]")]));
+    assert_eq!(check(9, 5), None);
+    assert_eq!(check(2, 1), None);
+}
+
+#[test]
+fn test_is_exported() {
+    let env = QueryEnv::new(&[
+        (
+            "gated",
+            r#"
+from typing import List as List
+__all__ = ["x", "_y"]
+x = 1
+_y = 2
+z = 3
+"#,
+        ),
+        (
+            "convention",
+            r#"
+from typing import List as List
+from typing import Dict
+x = 1
+_y = 2
+__version__ = "1.0"
+"#,
+        ),
+    ]);
+    let (name, path) = env.file("gated");
+    let check = |symbol| env.query.is_exported(name, path.clone(), symbol);
+    assert_eq!(check("x"), Some(true));
+    assert_eq!(check("_y"), Some(true));
+    assert_eq!(check("z"), Some(false));
+    assert_eq!(check("List"), Some(false));
+    assert_eq!(check("missing"), None);
+
+    let (name, path) = env.file("convention");
+    let check = |symbol| env.query.is_exported(name, path.clone(), symbol);
+    assert_eq!(check("x"), Some(true));
+    assert_eq!(check("_y"), Some(false));
+    assert_eq!(check("__version__"), Some(true));
+    assert_eq!(check("List"), Some(true));
+    // Outside of stubs, any import that isn't private is re-exported.
+    assert_eq!(check("Dict"), Some(true));
+    assert_eq!(check("missing"), None);
+}
+
+#[test]
+fn test_type_parameters() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+from typing import Generic, TypeVar, TypeVarTuple, Unpack
+T = TypeVar("T", bound=int, covariant=True)
+Ts = TypeVarTuple("Ts")
+class A(Generic[T, Unpack[Ts]]):
+    pass
+def f[U: (int, str) = int, **P](x: U) -> U: ...
+def g(x: int) -> int: ...
+import sys
+if sys.version_info < (3, 0):
+    class Old(Generic[T]): pass
+    def old[V](x: V) -> V: ...
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let check = |line, col| env.query.type_parameters(name, path.clone(), line, col);
+    assert_eq!(
+        check(6, 5),
+        Some(vec![
+            TypeParamInfo {
+                name: "T".to_owned(),
+                kind: QuantifiedKind::TypeVar,
+                bound: Some("int".to_owned()),
+                constraints: Vec::new(),
+                variance: PreInferenceVariance::PCovariant,
+                default: None,
+            },
+            TypeParamInfo {
+                name: "Ts".to_owned(),
+                kind: QuantifiedKind::TypeVarTuple,
+                bound: None,
+                constraints: Vec::new(),
+                variance: PreInferenceVariance::PInvariant,
+                default: None,
+            },
+        ])
+    );
+    assert_eq!(
+        check(7, 5),
+        Some(vec![
+            TypeParamInfo {
+                name: "U".to_owned(),
+                kind: QuantifiedKind::TypeVar,
+                bound: None,
+                constraints: vec!["int".to_owned(), "str".to_owned()],
+                variance: PreInferenceVariance::PUndefined,
+                default: Some("int".to_owned()),
+            },
+            TypeParamInfo {
+                name: "P".to_owned(),
+                kind: QuantifiedKind::ParamSpec,
+                bound: None,
+                constraints: Vec::new(),
+                variance: PreInferenceVariance::PUndefined,
+                default: None,
+            },
+        ])
+    );
+    assert_eq!(check(8, 5), Some(Vec::new()));
+    assert_eq!(check(2, 1), None);
+    // Classes and functions in a statically false branch have no bindings.
+    assert_eq!(check(11, 11), None);
+    assert_eq!(check(12, 9), None);
+}
+
+#[test]
+fn test_optional_accesses() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+def f(x: str | None, y: list[int] | None) -> None:
+    x.upper()
+    if x is not None:
+        x.upper()
+    if y:
+        y[0]
+    y[0]
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let res = env
+        .query
+        .optional_accesses(name, path)
+        .into_iter()
+        .map(|(range, ty)| (range.to_string(), ty))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        res,
+        vec![
+            ("3:5-12".to_owned(), "str | None".to_owned()),
+            ("8:5-9".to_owned(), "list[int] | None".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn test_fstring_interpolation_types() {
+    let env = QueryEnv::new(&[(
+        "main",
+        r#"
+def f(x: int, width: int, name: str) -> str:
+    return f"{name}: {x:{width}}"
+"#,
+    )]);
+    let (name, path) = env.file("main");
+    let res = env
+        .query
+        .fstring_interpolation_types(name, path)
+        .into_iter()
+        .map(|(range, ty)| (range.to_string(), ty))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        res,
+        vec![
+            ("3:15-19".to_owned(), "str".to_owned()),
+            ("3:23-24".to_owned(), "int".to_owned()),
+            // Expressions in a format spec are reported too.
+            ("3:26-31".to_owned(), "int".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn test_type_coverage() {
+    let env = QueryEnv::new(&[
+        (
+            "mixed",
+            r#"
+from typing import Any
+def f(x, y: Any):
+    return x, y
+"#,
+        ),
+        ("typed", "x: int = 1\ny = x + 1\n"),
+        ("empty", ""),
+    ]);
+    let coverage = |file| {
+        let (name, path) = env.file(file);
+        env.query.type_coverage(name, path)
+    };
+
+    let mixed = coverage("mixed");
+    // `y` was annotated as `Any`, while `x` has no annotation at all.
+    assert_eq!(mixed.explicit_any, 1);
+    assert_eq!(mixed.unknown, 1);
+    assert!(mixed.typed > 0);
+    assert_eq!(
+        mixed.percentage(),
+        mixed.typed as f64 * 100.0 / mixed.total() as f64
+    );
+
+    let typed = coverage("typed");
+    assert_eq!((typed.explicit_any, typed.unknown), (0, 0));
+    assert!(typed.typed > 0);
+    assert_eq!(typed.percentage(), 100.0);
+
+    assert_eq!(coverage("empty"), Coverage::default());
+    assert_eq!(coverage("empty").percentage(), 100.0);
+}
@@ -786,6 +786,30 @@ def f(x: Annotated[int, "test"], y: Annotated[int, "test", "test"]):
     "#,
 );
 
+testcase!(
+    test_annotated_in_union,
+    r#"
+from typing import Annotated, assert_type
+def f(x: int | Annotated[int, "meta"], y: Annotated[int, "a"] | Annotated[int, "b"] | str):
+    assert_type(x, int)
+    assert_type(y, int | str)
+    "#,
+);
+
+testcase!(
+    test_nested_optional,
+    r#"
+from typing import Any, Optional, assert_type
+def f(x: Optional[Optional[int]], y: Optional[int | None], z: Optional[Any]):
+    assert_type(x, int | None)
+    assert_type(y, int | None)
+    # `Any` deliberately doesn't absorb `None`, so that `z is not None` still narrows.
+    assert_type(z, Any | None)
+    if z is not None:
+        assert_type(z, Any)
+    "#,
+);
+
 testcase!(
     test_no_backtracking,
     r#"
@@ -5,6 +5,7 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use crate::test::util::TestEnv;
 use crate::testcase;
 
 testcase!(
@@ -38,11 +39,11 @@ testcase!(
 from typing import assert_type, Any
 def test(
     x1: tuple[int, ...], # OK
-    x2: tuple[...],  # E: Invalid position for `...`
-    x3: tuple[int, ..., ...],  # E: Invalid position for `...`
-    x4: tuple[int, ..., int],  # E: Invalid position for `...`
-    x5: tuple[int, int, ...],  # E: Invalid position for `...`
-    x6: tuple[..., int],  # E: Invalid position for `...`
+    x2: tuple[...],  # E: `...` is only allowed as the second of two elements: `tuple[T, ...]`
+    x3: tuple[int, ..., ...],  # E: `...` is only allowed as the second of two elements: `tuple[T, ...]`
+    x4: tuple[int, ..., int],  # E: `...` is only allowed as the second of two elements: `tuple[T, ...]`
+    x5: tuple[int, int, ...],  # E: `...` is only allowed as the second of two elements: `tuple[T, ...]`
+    x6: tuple[..., int],  # E: `...` is only allowed as the second of two elements: `tuple[T, ...]`
     x7: tuple[*tuple[int], ...]  # E: `...` cannot be used with an unpacked `TypeVarTuple` or tuple
 ):
     assert_type(x2, tuple[Any, ...])
@@ -54,6 +55,19 @@ def test(
 "#,
 );
 
+#[test]
+fn test_invalid_ellipsis_range() {
+    let (state, handle) = TestEnv::one("main", "x: tuple[int, ..., int]").to_state();
+    let errs = state
+        .transaction()
+        .get_errors([&handle("main")])
+        .collect_errors()
+        .shown;
+    assert_eq!(errs.len(), 1);
+    // The error points at the `...`, not the whole subscript.
+    assert_eq!(errs[0].display_range().to_string(), "1:15-18");
+}
+
 testcase!(
     test_index,
     r#"
@@ -68,6 +82,19 @@ def foo(x: tuple[int, str], y: tuple[int, ...], z: tuple[int, *tuple[str, ...],
 "#,
 );
 
+testcase!(
+    test_index_type,
+    r#"
+from typing import assert_type
+def foo(x: tuple[int, str], y: tuple[int, ...], b: bool) -> None:
+    assert_type(x[True], str)
+    assert_type(x[b], int | str)
+    assert_type(y[b], int)
+    x["0"]  # E: Cannot index into `tuple[int, str]`, expected an integer or slice
+    y["0"]  # E: Cannot index into `tuple[int, ...]`, expected an integer or slice
+"#,
+);
+
 testcase!(
     test_empty_tuple,
     r#"
@@ -117,6 +144,45 @@ def test(x: tuple[int, ...], y: str) -> None:
 "#,
 );
 
+testcase!(
+    test_unpack_in_return,
+    r#"
+from typing import Any
+def f(x: tuple[int, str], y: bytes) -> tuple[int, str, bytes]:
+  return *x, y
+def g(x: tuple[int, str]) -> tuple[int, str, bytes]:
+  return *x, 1  # E: is not assignable to declared return type `tuple[int, str, bytes]`
+def h(x: tuple[int, ...]) -> tuple[int, int]:
+  return 1, *x  # E: A variable-length tuple cannot be returned where a tuple of length 2 is expected
+def i(x: tuple[int, ...]) -> tuple[int, *tuple[int, ...]]:
+  return 1, *x
+def j(x: tuple[Any, ...]) -> tuple[int, int]:
+  return 1, *x
+"#,
+);
+
+testcase!(
+    test_identical_tuple_solve,
+    r#"
+from typing import Any
+def test(
+    a: tuple[int, str],
+    b: tuple[int, *tuple[str, ...], bytes],
+    c: tuple[Any, int],
+    d: tuple[int, int],
+) -> None:
+  x1: tuple[int, str] = a
+  x2: tuple[int, *tuple[str, ...], bytes] = b
+  x3: tuple[Any, int] = c
+  x4: tuple[int, int] = c
+  x5: tuple[Any, int] = d
+  x6: tuple[int, bytes] = a  # E: `tuple[int, str]` is not assignable to `tuple[int, bytes]`
+  x7: tuple[int, *tuple[str, ...], str] = b  # E: `tuple[int, *tuple[str, ...], bytes]` is not assignable to `tuple[int, *tuple[str, ...], str]`
+  x8: tuple[str, int] = c
+  x9: tuple[Any, str] = d  # E: `tuple[int, int]` is not assignable to `tuple[Any, str]`
+"#,
+);
+
 testcase!(
     test_unbounded_solve,
     r#"
@@ -204,6 +270,20 @@ x5: list[int] = ("err",)  # E: `tuple[Literal['err']]` is not assignable to `lis
     "#,
 );
 
+testcase!(
+    test_tuple_collection_protocols,
+    r#"
+from typing import Collection, Container, Reversible, Sized
+def f(t: tuple[int, str], u: tuple[int, ...]) -> None:
+    x1: Collection[int | str] = t
+    x2: Container[int | str] = t
+    x3: Reversible[int | str] = t
+    x4: Sized = t
+    x5: Reversible[int] = u
+    x6: Collection[int] = t  # E: `tuple[int, str]` is not assignable to `Collection[int]`
+    "#,
+);
+
 testcase!(
     test_empty_tuple_hint,
     r#"
@@ -235,6 +315,19 @@ for x in f():
     "#,
 );
 
+testcase!(
+    test_iterate_union_with_empty,
+    r#"
+from typing import assert_type, Literal
+def f() -> tuple[int, str] | tuple[()] | tuple[bool, ...]: ...
+for x in f():
+    assert_type(x, int | bool | str)
+def g() -> tuple[Literal[True]] | tuple[()] | tuple[int, *tuple[Literal[False], ...]]: ...
+for y in g():
+    assert_type(y, bool | int)
+    "#,
+);
+
 testcase!(
     test_tuple_parent,
     r#"
@@ -334,6 +427,62 @@ def test(x: tuple[int] | tuple[str]) -> None:
 "#,
 );
 
+testcase!(
+    test_assert_type_non_empty_homogeneous_tuple,
+    r#"
+from typing import assert_type
+def test(x: tuple[*tuple[int, ...], int]) -> None:
+    assert_type(x, tuple[int, *tuple[int, ...]])
+    assert_type(x, tuple[int, ...])  # E: assert_type(tuple[*tuple[int, ...], int], tuple[int, ...]) failed
+"#,
+);
+
+testcase!(
+    test_tuple_multiply,
+    r#"
+from typing import assert_type
+def test(x: tuple[int, str], y: tuple[int, ...], n: int) -> None:
+    assert_type(x * 2, tuple[int, str, int, str])
+    assert_type(2 * x, tuple[int, str, int, str])
+    assert_type(x * 0, tuple[()])
+    assert_type(x * -1, tuple[()])
+    assert_type(x * n, tuple[int | str, ...])
+    assert_type(y * 2, tuple[int, ...])
+    assert_type((1, 2) * n, tuple[int, ...])
+    assert_type(() * n, tuple[()])
+"#,
+);
+
+testcase!(
+    test_assert_type_tuple_mismatch,
+    r#"
+from typing import assert_type
+def test(x: tuple[int, str]) -> None:
+    assert_type(x + x, tuple[int, str, bytes, str])  # E: element 2: expected bytes, got int
+    assert_type(x + x, tuple[int, str])  # E: tuple lengths differ: expected 2, got 4
+"#,
+);
+
+testcase!(
+    test_long_tuple_widens,
+    TestEnv::new_with_max_concrete_tuple_length(3),
+    r#"
+from typing import assert_type, Literal
+assert_type((1, 2), tuple[Literal[1], Literal[2]])
+x = (1, 2, 3, 4)
+assert_type(x, tuple[int, ...])
+assert_type((1, 2) + (3, 4), tuple[int, ...])
+assert_type((1, 2) * 2, tuple[int, ...])
+# Tuples checked against an annotation are never widened.
+y: tuple[int, int, int, int] = (1, 2, 3, 4)
+z: tuple[int, int, int, int] = (1, 2) + (3, 4)
+w: tuple[int, int, int, int] = (1, 2) * 2
+def f(a: tuple[int, int], b: tuple[int, int]) -> tuple[int, int, int, int]:
+    c: tuple[int, int, int, int] = a + b
+    return a + b
+"#,
+);
+
 testcase!(
     bug = "Pyrefly hangs on this example if we uncomment the second definition of f",
     test_unpack_tuple_with_double_def,
@@ -32,6 +32,7 @@ use ruff_text_size::TextSize;
 use starlark_map::small_map::SmallMap;
 
 use crate::binding::binding::KeyExport;
+use crate::config::base::ImplicitOptional;
 use crate::config::base::UntypedDefBehavior;
 use crate::config::config::ConfigFile;
 use crate::config::finder::ConfigFinder;
@@ -96,6 +97,8 @@ pub struct TestEnv {
     modules: SmallMap<ModuleName, (ModulePath, Option<Arc<String>>)>,
     version: PythonVersion,
     untyped_def_behavior: UntypedDefBehavior,
+    implicit_optional: ImplicitOptional,
+    max_concrete_tuple_length: Option<usize>,
 }
 
 impl TestEnv {
@@ -117,6 +120,18 @@ impl TestEnv {
         res
     }
 
+    pub fn new_with_implicit_optional(implicit_optional: ImplicitOptional) -> Self {
+        let mut res = Self::new();
+        res.implicit_optional = implicit_optional;
+        res
+    }
+
+    pub fn new_with_max_concrete_tuple_length(max_concrete_tuple_length: usize) -> Self {
+        let mut res = Self::new();
+        res.max_concrete_tuple_length = Some(max_concrete_tuple_length);
+        res
+    }
+
     pub fn add_with_path(&mut self, name: &str, path: &str, code: &str) {
         assert!(
             path.ends_with(".py") || path.ends_with(".pyi") || path.ends_with(".rs"),
@@ -178,6 +193,8 @@ impl TestEnv {
         config.python_environment.python_platform = Some(PythonPlatform::linux());
         config.python_environment.site_package_path = Some(Vec::new());
         config.root.untyped_def_behavior = Some(self.untyped_def_behavior);
+        config.root.implicit_optional = Some(self.implicit_optional);
+        config.root.max_concrete_tuple_length = self.max_concrete_tuple_length;
         for (name, (path, _)) in self.modules.iter() {
             config.custom_module_paths.insert(*name, path.clone());
         }
@@ -21,7 +21,9 @@ use starlark_map::smallmap;
 
 use crate::module::module_name::ModuleName;
 use crate::types::callable::Function;
+use crate::types::literal::Lit;
 use crate::types::qname::QName;
+use crate::types::quantified::Quantified;
 use crate::types::tuple::Tuple;
 use crate::types::types::AnyStyle;
 use crate::types::types::BoundMethod;
@@ -77,11 +79,37 @@ impl ClassInfo {
     }
 }
 
+/// How to spell the bottom type when displaying it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NeverSpelling {
+    /// Always display `Never`.
+    Never,
+    /// Always display `NoReturn`.
+    NoReturn,
+    /// Display `Never` or `NoReturn` depending on how the user wrote it.
+    /// An empty union, which has no style, is displayed as `Never`.
+    #[default]
+    Preserve,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct TypeDisplayContext<'a> {
     classes: SmallMap<&'a Name, ClassInfo>,
+    /// The distinct type parameters we have seen with each name, in the order we saw them.
+    /// If there is more than one, they are displayed with a suffix, e.g. `T@1` and `T@2`.
+    quantifieds: SmallMap<&'a Name, Vec<&'a Quantified>>,
+    /// Render all the literals of a union first, followed by the remaining members.
+    group_union_members: bool,
+    never_spelling: NeverSpelling,
+    /// Render runs of consecutive int literals as a range, e.g. `Literal[0..=9]`.
+    summarize_int_literals: bool,
+    /// Render references to the type alias with this name as just the name.
+    alias_by_name: Option<Name>,
 }
 
+/// The shortest run of consecutive int literals that `summarize_int_literals` turns into a range.
+const MIN_INT_LITERAL_RUN: usize = 4;
+
 impl<'a> TypeDisplayContext<'a> {
     pub fn new(xs: &[&'a Type]) -> Self {
         let mut res = Self::default();
@@ -101,6 +129,13 @@ impl<'a> TypeDisplayContext<'a> {
                 Type::TypeVarTuple(t) => Some(t.qname()),
                 Type::ParamSpec(t) => Some(t.qname()),
                 Type::SelfType(cls) => Some(cls.qname()),
+                Type::Quantified(q) => {
+                    let seen = self.quantifieds.entry(q.name()).or_default();
+                    if !seen.contains(&q) {
+                        seen.push(q);
+                    }
+                    None
+                }
                 _ => None,
             };
             if let Some(qname) = qname {
@@ -123,6 +158,72 @@ impl<'a> TypeDisplayContext<'a> {
         }
     }
 
+    /// Display the members of a union grouped by kind: all literals collapsed together first,
+    /// followed by everything else in their original order. Only affects display.
+    pub fn group_union_members(&mut self) {
+        self.group_union_members = true;
+    }
+
+    /// Control whether the bottom type is displayed as `Never`, `NoReturn`, or as written.
+    pub fn never_spelling(&mut self, spelling: NeverSpelling) {
+        self.never_spelling = spelling;
+    }
+
+    /// Display runs of four or more consecutive int literals in a union as a range, e.g.
+    /// `Literal[0..=9]` rather than listing every value. Only affects display.
+    pub fn summarize_int_literals(&mut self) {
+        self.summarize_int_literals = true;
+    }
+
+    /// Display references to the type alias `name` as just its name, rather than the type it
+    /// aliases, e.g. so the body of a recursive alias refers back to the alias. Only affects
+    /// display.
+    pub fn alias_by_name(&mut self, name: Name) {
+        self.alias_by_name = Some(name);
+    }
+
+    fn fmt_literals(&self, literals: &[&Lit]) -> String {
+        if !self.summarize_int_literals {
+            return commas_iter(|| literals).to_string();
+        }
+        let int = |x: &Lit| match x {
+            Lit::Int(x) => x.as_i64(),
+            _ => None,
+        };
+        let mut res = Vec::new();
+        let mut i = 0;
+        while i < literals.len() {
+            let mut j = i + 1;
+            if let Some(mut prev) = int(literals[i]) {
+                while j < literals.len()
+                    && let Some(next) = int(literals[j])
+                    && prev.checked_add(1) == Some(next)
+                {
+                    prev = next;
+                    j += 1;
+                }
+            }
+            if j - i >= MIN_INT_LITERAL_RUN {
+                res.push(format!("{}..={}", literals[i], literals[j - 1]));
+            } else {
+                res.extend(literals[i..j].iter().map(|x| x.to_string()));
+            }
+            i = j;
+        }
+        res.join(", ")
+    }
+
+    fn fmt_never(&self, style: NeverStyle, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.never_spelling, style) {
+            (NeverSpelling::Never, _) | (NeverSpelling::Preserve, NeverStyle::Never) => {
+                write!(f, "Never")
+            }
+            (NeverSpelling::NoReturn, _) | (NeverSpelling::Preserve, NeverStyle::NoReturn) => {
+                write!(f, "NoReturn")
+            }
+        }
+    }
+
     pub fn display(&'a self, t: &'a Type) -> impl Display + 'a {
         Fmt(|f| self.fmt(t, f))
     }
@@ -174,6 +275,16 @@ impl<'a> TypeDisplayContext<'a> {
         }
     }
 
+    fn fmt_quantified(&self, q: &Quantified, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.quantifieds.get(q.name()) {
+            Some(seen) if seen.len() > 1 => {
+                let i = seen.iter().position(|x| *x == q).unwrap_or_default();
+                write!(f, "{q}@{}", i + 1)
+            }
+            _ => write!(f, "{q}"),
+        }
+    }
+
     fn fmt_qname(&self, qname: &QName, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.classes.get(&qname.id()) {
             Some(info) => info.fmt(qname, f),
@@ -183,6 +294,11 @@ impl<'a> TypeDisplayContext<'a> {
 
     fn fmt<'b>(&self, t: &'b Type, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match t {
+            Type::TypeAlias(ta)
+            | Type::Forall(box Forall {
+                body: Forallable::TypeAlias(ta),
+                ..
+            }) if self.alias_by_name.as_ref() == Some(&*ta.name) => write!(f, "{}", ta.name),
             // Things that have QName's and need qualifying
             Type::ClassDef(cls) => {
                 write!(f, "type[")?;
@@ -190,7 +306,7 @@ impl<'a> TypeDisplayContext<'a> {
                 write!(f, "]")
             }
             Type::ClassType(class_type)
-                if class_type.qname().module_name().as_str() == "builtins"
+                if class_type.qname().module_name() == "builtins"
                     && class_type.qname().id().as_str() == "tuple"
                     && class_type.targs().as_slice().len() == 1 =>
             {
@@ -269,9 +385,8 @@ impl<'a> TypeDisplayContext<'a> {
                     self.display(&func.as_type())
                 )
             }
-            Type::Never(NeverStyle::NoReturn) => write!(f, "NoReturn"),
-            Type::Never(NeverStyle::Never) => write!(f, "Never"),
-            Type::Union(types) if types.is_empty() => write!(f, "Never"),
+            Type::Never(style) => self.fmt_never(*style, f),
+            Type::Union(types) if types.is_empty() => self.fmt_never(NeverStyle::Never, f),
             Type::Union(types) => {
                 // All Literals will be collected into a single Literal at the index of the first Literal.
                 let mut literal_idx = None;
@@ -292,7 +407,8 @@ impl<'a> TypeDisplayContext<'a> {
                     }
                 }
                 if let Some(i) = literal_idx {
-                    display_types.insert(i, format!("Literal[{}]", commas_iter(|| &literals)));
+                    let i = if self.group_union_members { 0 } else { i };
+                    display_types.insert(i, format!("Literal[{}]", self.fmt_literals(&literals)));
                 }
                 write!(f, "{}", display_types.join(" | "))
             }
@@ -311,7 +427,9 @@ impl<'a> TypeDisplayContext<'a> {
                 write!(
                     f,
                     "[{}]{}",
-                    commas_iter(|| tparams.iter()),
+                    commas_iter(|| tparams
+                        .iter()
+                        .map(|x| Fmt(|f| self.fmt_quantified(&x.quantified, f)))),
                     self.display(&body.clone().as_type()),
                 )
             }
@@ -331,7 +449,7 @@ impl<'a> TypeDisplayContext<'a> {
             ),
             Type::Module(m) => write!(f, "Module[{m}]"),
             Type::Var(var) => write!(f, "{var}"),
-            Type::Quantified(var) => write!(f, "{var}"),
+            Type::Quantified(var) => self.fmt_quantified(var, f),
             Type::Args(q) => {
                 write!(f, "Args[{q}]")
             }
@@ -342,7 +460,9 @@ impl<'a> TypeDisplayContext<'a> {
             Type::Ellipsis => write!(f, "Ellipsis"),
             Type::Any(style) => match style {
                 AnyStyle::Explicit => write!(f, "Any"),
-                AnyStyle::Implicit | AnyStyle::Error => write!(f, "Unknown"),
+                AnyStyle::Implicit | AnyStyle::Error | AnyStyle::Unresolved => {
+                    write!(f, "Unknown")
+                }
             },
             Type::TypeAlias(ta) => ta.fmt_with_type(f, &|t| self.display(t), None),
             Type::SuperInstance(box (cls, obj)) => {
@@ -381,6 +501,7 @@ pub mod tests {
     use std::sync::Arc;
 
     use dupe::Dupe;
+    use pyrefly_util::prelude::SliceExt;
     use pyrefly_util::uniques::UniqueFactory;
     use ruff_python_ast::Identifier;
     use ruff_text_size::TextSize;
@@ -395,6 +516,7 @@ pub mod tests {
     use crate::types::class::Class;
     use crate::types::class::ClassDefIndex;
     use crate::types::class::ClassType;
+    use crate::types::lit_int::LitInt;
     use crate::types::literal::Lit;
     use crate::types::quantified::Quantified;
     use crate::types::quantified::QuantifiedInfo;
@@ -406,6 +528,8 @@ pub mod tests {
     use crate::types::typed_dict::TypedDict;
     use crate::types::types::TParam;
     use crate::types::types::TParams;
+    use crate::types::types::TypeAlias;
+    use crate::types::types::TypeAliasStyle;
 
     pub fn fake_class(name: &str, module: &str, range: u32) -> Class {
         let mi = ModuleInfo::new(
@@ -611,6 +735,27 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_display_quantified() {
+        let uniques = UniqueFactory::new();
+        let t1 = fake_tparam(&uniques, "T", QuantifiedKind::TypeVar).quantified;
+        let t2 = fake_tparam(&uniques, "T", QuantifiedKind::TypeVar).quantified;
+        let u = fake_tparam(&uniques, "U", QuantifiedKind::TypeVar).quantified;
+
+        assert_eq!(
+            Type::Union(vec![t1.clone().to_type(), t2.clone().to_type()]).to_string(),
+            "T@1 | T@2"
+        );
+        assert_eq!(
+            Type::Union(vec![t1.clone().to_type(), u.to_type()]).to_string(),
+            "T | U"
+        );
+        assert_eq!(
+            Type::Union(vec![t1.clone().to_type(), t1.to_type()]).to_string(),
+            "T | T"
+        );
+    }
+
     #[test]
     fn test_display_literal() {
         assert_eq!(Type::Literal(Lit::Bool(true)).to_string(), "Literal[True]");
@@ -637,6 +782,118 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_display_union_grouped() {
+        let lit1 = Type::Literal(Lit::Bool(true));
+        let lit2 = Type::Literal(Lit::Str("test".into()));
+        let nonlit1 = Type::None;
+        let nonlit2 = Type::LiteralString;
+
+        let t = Type::Union(vec![nonlit1, lit1.clone(), nonlit2, lit2.clone()]);
+        let mut ctx = TypeDisplayContext::new(&[&t]);
+        ctx.group_union_members();
+        assert_eq!(
+            ctx.display(&t).to_string(),
+            "Literal[True, 'test'] | None | LiteralString"
+        );
+
+        let t = Type::Union(vec![lit1, lit2]);
+        let mut ctx = TypeDisplayContext::new(&[&t]);
+        ctx.group_union_members();
+        assert_eq!(ctx.display(&t).to_string(), "Literal[True, 'test']");
+    }
+
+    #[test]
+    fn test_display_summarize_int_literals() {
+        let ints = |xs: &[i64]| Type::Union(xs.map(|x| Type::Literal(Lit::Int(LitInt::new(*x)))));
+        let display = |t: &Type| {
+            let mut ctx = TypeDisplayContext::new(&[t]);
+            ctx.summarize_int_literals();
+            ctx.display(t).to_string()
+        };
+        let contiguous = ints(&(0..10).collect::<Vec<_>>());
+        assert_eq!(display(&contiguous), "Literal[0..=9]");
+        assert_eq!(
+            contiguous.to_string(),
+            "Literal[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]"
+        );
+        assert_eq!(
+            display(&ints(&[0, 1, 3, 5, 6, 7, 8, 10])),
+            "Literal[0, 1, 3, 5..=8, 10]"
+        );
+    }
+
+    #[test]
+    fn test_display_alias_by_name() {
+        let alias = |name: &str, ty: Type| {
+            Type::TypeAlias(TypeAlias::new(
+                Name::new(name),
+                ty,
+                TypeAliasStyle::LegacyExplicit,
+            ))
+        };
+        let t = Type::Union(vec![
+            alias("T", Type::None),
+            alias("TT", Type::None),
+            Type::None,
+        ]);
+        let mut ctx = TypeDisplayContext::new(&[&t]);
+        ctx.alias_by_name(Name::new_static("T"));
+        assert_eq!(
+            ctx.display(&t).to_string(),
+            "T | TypeAlias[TT, None] | None"
+        );
+    }
+
+    #[test]
+    fn test_display_union_unresolved() {
+        // The degraded marker isn't part of the type's display, so nested unions stay valid
+        // type expressions.
+        let union = Type::Union(vec![Type::None, Type::any_unresolved()]);
+        assert_eq!(union.to_string(), "None | Unknown");
+        assert!(union.is_degraded_by_unresolved_import());
+        let nested = Type::Callable(Box::new(Callable::list(
+            ParamList::new(vec![Param::Pos(
+                Name::new_static("x"),
+                union,
+                Required::Required,
+            )]),
+            Type::None,
+        )));
+        assert_eq!(nested.to_string(), "(x: None | Unknown) -> None");
+        assert!(nested.is_degraded_by_unresolved_import());
+        assert!(
+            !Type::Union(vec![Type::None, Type::any_error()]).is_degraded_by_unresolved_import()
+        );
+    }
+
+    #[test]
+    fn test_display_never_spelling() {
+        let never = Type::Never(NeverStyle::Never);
+        let no_return = Type::Never(NeverStyle::NoReturn);
+        let empty = Type::Union(Vec::new());
+        let display = |spelling: Option<NeverSpelling>| {
+            let mut ctx = TypeDisplayContext::new(&[&never, &no_return, &empty]);
+            if let Some(spelling) = spelling {
+                ctx.never_spelling(spelling);
+            }
+            [&never, &no_return, &empty].map(|t| ctx.display(t).to_string())
+        };
+        assert_eq!(display(None), ["Never", "NoReturn", "Never"]);
+        assert_eq!(
+            display(Some(NeverSpelling::Preserve)),
+            ["Never", "NoReturn", "Never"]
+        );
+        assert_eq!(
+            display(Some(NeverSpelling::Never)),
+            ["Never", "Never", "Never"]
+        );
+        assert_eq!(
+            display(Some(NeverSpelling::NoReturn)),
+            ["NoReturn", "NoReturn", "NoReturn"]
+        );
+    }
+
     #[test]
     fn test_display_callable() {
         let param1 = Param::Pos(Name::new_static("hello"), Type::None, Required::Required);
@@ -78,9 +78,9 @@ impl LitInt {
     pub fn from_ast(x: &Int) -> Self {
         match x.as_i64() {
             Some(x) => Self(LitIntInner::Small(x)),
-            None => Self(LitIntInner::Big(Box::new(parse_ruff_int_str(
-                &x.to_string(),
-            )))),
+            // Go through `new_big` so a value in the range of i64 is still stored as `Small`,
+            // since equality relies on each value having a single representation.
+            None => Self::new_big(parse_ruff_int_str(&x.to_string())),
         }
     }
 
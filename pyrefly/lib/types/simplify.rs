@@ -5,30 +5,93 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use pyrefly_util::lock::Mutex;
 use starlark_map::small_map::SmallMap;
+use starlark_map::small_set::SmallSet;
 
+use crate::types::callable::Callable;
+use crate::types::callable::Param;
+use crate::types::callable::Params;
 use crate::types::literal::Lit;
 use crate::types::stdlib::Stdlib;
 use crate::types::tuple::Tuple;
+use crate::types::types::AnyStyle;
 use crate::types::types::Type;
 
 /// Turn unions of unions into a flattened list for one union, and return the deduped list.
-fn flatten_and_dedup(xs: Vec<Type>) -> Vec<Type> {
-    fn flatten(xs: Vec<Type>, res: &mut Vec<Type>) {
+/// Given a `Stdlib`, the `NoneType` class is first canonicalized to `None`, so the two spellings
+/// dedup. Note that `type[None]` is a different type, and is left alone.
+/// There is no need to strip `Annotated`, as its metadata is dropped when the annotation is
+/// resolved, so `int | Annotated[int, "a"] | Annotated[int, "b"]` is already `int | int | int`.
+/// Also returns whether any `Literal` or `LiteralString` members were seen, so callers can
+/// skip literal simplification without another pass over the list.
+/// `Never` members of either style are dropped, so a union made only of them collapses to
+/// `Type::never()`: `Never` takes precedence over `NoReturn`, whatever the order of the members.
+fn flatten_and_dedup(xs: Vec<Type>, stdlib: Option<&Stdlib>) -> (Vec<Type>, bool) {
+    fn flatten(
+        xs: Vec<Type>,
+        stdlib: Option<&Stdlib>,
+        res: &mut Vec<Type>,
+        has_literals: &mut bool,
+    ) {
         for x in xs {
             match x {
-                Type::Union(xs) => flatten(xs, res),
+                Type::Union(xs) => flatten(xs, stdlib, res, has_literals),
                 Type::Never(_) => {}
+                Type::ClassType(cls) if stdlib.is_some_and(|s| s.is_none_type(&cls)) => {
+                    res.push(Type::None)
+                }
+                Type::Literal(_) | Type::LiteralString => {
+                    *has_literals = true;
+                    res.push(x)
+                }
                 _ => res.push(x),
             }
         }
     }
     let mut res = Vec::with_capacity(xs.len());
-    flatten(xs, &mut res);
+    let mut has_literals = false;
+    flatten(xs, stdlib, &mut res, &mut has_literals);
 
     res.sort();
     res.dedup();
-    res
+    dedup_callables(&mut res);
+    // A member from an unresolved import marks the union as degraded. Implicit and error `Any`s
+    // would display as the same `Unknown`, so drop them in favor of the one that keeps the mark.
+    if res.contains(&Type::any_unresolved()) {
+        res.retain(|x| !matches!(x, Type::Any(AnyStyle::Implicit | AnyStyle::Error)));
+    }
+    (res, has_literals)
+}
+
+/// Remove callables that only differ from an earlier callable in the names of their
+/// positional-only parameters, since callers can't tell them apart.
+fn dedup_callables(xs: &mut Vec<Type>) {
+    fn erase_pos_only_names(callable: &Callable) -> Callable {
+        let mut callable = callable.clone();
+        if let Params::List(params) = &mut callable.params {
+            for param in params.items_mut() {
+                if let Param::PosOnly(name, _, _) = param {
+                    *name = None;
+                }
+            }
+        }
+        callable
+    }
+
+    if xs
+        .iter()
+        .filter(|x| matches!(x, Type::Callable(_)))
+        .nth(1)
+        .is_none()
+    {
+        return;
+    }
+    let mut seen = SmallSet::new();
+    xs.retain(|x| match x {
+        Type::Callable(callable) => seen.insert(erase_pos_only_names(callable)),
+        _ => true,
+    });
 }
 
 /// Given a list of types to union together,
@@ -47,13 +110,21 @@ fn try_collapse(mut xs: Vec<Type>) -> Result<Type, Vec<Type>> {
 
 fn unions_internal(xs: Vec<Type>, stdlib: Option<&Stdlib>) -> Type {
     try_collapse(xs).unwrap_or_else(|xs| {
-        let mut res = flatten_and_dedup(xs);
-        if let Some(stdlib) = stdlib {
+        let (res, has_literals) = flatten_and_dedup(xs, stdlib);
+        collapse_flattened(res, has_literals, stdlib)
+    })
+}
+
+/// The simplifications that come after `flatten_and_dedup`.
+fn collapse_flattened(mut res: Vec<Type>, has_literals: bool, stdlib: Option<&Stdlib>) -> Type {
+    if let Some(stdlib) = stdlib {
+        if has_literals {
             collapse_literals(&mut res, stdlib);
         }
-        // `res` is collapsible again if `flatten_and_dedup` drops `xs` to 0 or 1 elements
-        try_collapse(res).unwrap_or_else(Type::Union)
-    })
+        collapse_gradual_tuples(&mut res);
+    }
+    // `res` is collapsible again if `flatten_and_dedup` drops `xs` to 0 or 1 elements
+    try_collapse(res).unwrap_or_else(Type::Union)
 }
 
 /// Union a set of types together, simplifying as much as you can.
@@ -67,6 +138,62 @@ pub fn unions_with_literals(xs: Vec<Type>, stdlib: &Stdlib) -> Type {
     unions_internal(xs, Some(stdlib))
 }
 
+/// Unions with fewer members than this are cheap enough that `UnionsCache` doesn't remember them.
+const MIN_CACHED_UNION_LEN: usize = 8;
+
+/// The most results `UnionsCache` holds before it starts over.
+const MAX_CACHED_UNIONS: usize = 64;
+
+/// Remembers the results of `unions_with_literals` for recent large inputs containing literals,
+/// so that unioning the same types again, as happens repeatedly while solving loops, skips
+/// collapsing the literals, which is the expensive part. The key is the flattened, sorted and
+/// deduplicated members, so inputs that differ only in order or duplicates share an entry.
+/// Without literals, collapsing is a cheap scan, so those unions aren't remembered and don't pay
+/// for copying the key. A cache must only be used with a single `Stdlib`: each `Solver` owns one,
+/// so it is discarded along with the module's answers.
+#[derive(Debug, Default)]
+pub struct UnionsCache(Mutex<SmallMap<Vec<Type>, Type>>);
+
+impl UnionsCache {
+    pub fn unions_with_literals(&self, xs: Vec<Type>, stdlib: &Stdlib) -> Type {
+        try_collapse(xs).unwrap_or_else(|xs| {
+            let (res, has_literals) = flatten_and_dedup(xs, Some(stdlib));
+            if !has_literals || res.len() < MIN_CACHED_UNION_LEN {
+                return collapse_flattened(res, has_literals, Some(stdlib));
+            }
+            if let Some(cached) = self.0.lock().get(&res) {
+                return cached.clone();
+            }
+            let collapsed = collapse_flattened(res.clone(), has_literals, Some(stdlib));
+            let mut cache = self.0.lock();
+            if cache.len() >= MAX_CACHED_UNIONS {
+                cache.clear();
+            }
+            cache.insert(res, collapsed.clone());
+            collapsed
+        })
+    }
+}
+
+/// If `tuple[Any, ...]` is present, drop all other concrete and unbounded tuples, since
+/// `tuple[Any, ...]` is gradual and absorbs them. A partially gradual tuple such as
+/// `tuple[Any, int]` absorbs nothing.
+fn collapse_gradual_tuples(types: &mut Vec<Type>) {
+    let Some(gradual) = types
+        .iter()
+        .position(|x| matches!(x, Type::Tuple(Tuple::Unbounded(box Type::Any(_)))))
+    else {
+        return;
+    };
+    let mut i = 0;
+    types.retain(|x| {
+        let keep =
+            i == gradual || !matches!(x, Type::Tuple(Tuple::Concrete(_) | Tuple::Unbounded(_)));
+        i += 1;
+        keep
+    });
+}
+
 /// Perform all literal transformations we can think of.
 ///
 /// 1. Literal[True, False] ==> bool
@@ -74,7 +201,9 @@ pub fn unions_with_literals(xs: Vec<Type>, stdlib: &Stdlib) -> Type {
 /// 3. LiteralString | str => str
 /// 3. LiteralString | Literal["x"] => LiteralString
 fn collapse_literals(types: &mut Vec<Type>, stdlib: &Stdlib) {
-    // All literal types we see, plus `true` to indicate they are found
+    // All literal types we see, plus `true` to indicate they are found.
+    // Keyed by the general class of each literal, never the literal value itself,
+    // so large values (e.g. bignum ints) are never hashed.
     let mut literal_types = SmallMap::new();
     // Specific flags to watch out for
     let mut has_literal_string = false;
@@ -160,46 +289,166 @@ pub fn simplify_tuples(tuple: Tuple) -> Type {
         Tuple::Concrete(elts) => {
             Type::Tuple(Tuple::Concrete(flatten_unpacked_concrete_tuples(elts)))
         }
-        Tuple::Unpacked(box (prefix, middle @ Type::Tuple(_), suffix))
-            if prefix.is_empty() && suffix.is_empty() =>
-        {
-            middle
-        }
-        Tuple::Unpacked(box (prefix, middle, suffix)) => match middle {
-            Type::Tuple(Tuple::Concrete(elts)) => {
-                Type::Tuple(Tuple::Concrete(flatten_unpacked_concrete_tuples(
-                    prefix
-                        .into_iter()
-                        .chain(elts)
-                        .chain(suffix)
-                        .collect::<Vec<_>>(),
-                )))
+        Tuple::Unpacked(box (prefix, mut middle, suffix)) => {
+            // Flatten nested unpacked tuples with a loop rather than recursion, so that
+            // deeply nested tuples can't overflow the stack. Suffixes are collected from the
+            // outside in, so are reversed at the end.
+            let mut prefix = flatten_unpacked_concrete_tuples(prefix);
+            let mut suffixes = vec![flatten_unpacked_concrete_tuples(suffix)];
+            loop {
+                match middle {
+                    Type::Tuple(Tuple::Unpacked(box (m_prefix, m_middle, m_suffix))) => {
+                        prefix.extend(flatten_unpacked_concrete_tuples(m_prefix));
+                        suffixes.push(flatten_unpacked_concrete_tuples(m_suffix));
+                        middle = m_middle;
+                    }
+                    Type::Tuple(Tuple::Concrete(elts)) => {
+                        prefix.extend(flatten_unpacked_concrete_tuples(elts));
+                        prefix.extend(suffixes.into_iter().rev().flatten());
+                        return Type::Tuple(Tuple::Concrete(prefix));
+                    }
+                    // The middle is already unpacked, so an explicit `Unpack` around it is redundant.
+                    Type::Unpack(box m_middle @ Type::Tuple(_)) => middle = m_middle,
+                    _ => break,
+                }
             }
-            Type::Tuple(Tuple::Unpacked(box (m_prefix, m_middle, m_suffix))) => {
-                let mut new_prefix = flatten_unpacked_concrete_tuples(prefix);
-                new_prefix.extend(flatten_unpacked_concrete_tuples(m_prefix));
-                let mut new_suffix = flatten_unpacked_concrete_tuples(m_suffix);
-                new_suffix.extend(flatten_unpacked_concrete_tuples(suffix));
-                Type::Tuple(Tuple::Unpacked(Box::new((
-                    new_prefix, m_middle, new_suffix,
-                ))))
+            let suffix = suffixes.into_iter().rev().flatten().collect::<Vec<_>>();
+            if prefix.is_empty() && suffix.is_empty() && matches!(middle, Type::Tuple(_)) {
+                middle
+            } else {
+                Type::Tuple(Tuple::Unpacked(Box::new((prefix, middle, suffix))))
             }
-            _ => Type::Tuple(Tuple::Unpacked(Box::new((
-                flatten_unpacked_concrete_tuples(prefix),
-                middle,
-                flatten_unpacked_concrete_tuples(suffix),
-            )))),
-        },
+        }
+        Tuple::Unbounded(box Type::Union(elts)) => Type::Tuple(Tuple::unbounded(unions(elts))),
         _ => Type::Tuple(tuple),
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Instant;
+
+    use ruff_python_ast::Int;
+    use ruff_python_ast::name::Name;
+
+    use crate::module::module_name::ModuleName;
+    use crate::python::sys_info::PythonVersion;
+    use crate::types::callable::Callable;
+    use crate::types::callable::Param;
+    use crate::types::callable::ParamList;
+    use crate::types::callable::Required;
+    use crate::types::class::ClassType;
+    use crate::types::display::tests::fake_class;
+    use crate::types::display::tests::fake_tparams;
+    use crate::types::lit_int::LitInt;
+    use crate::types::literal::Lit;
+    use crate::types::literal::LitEnum;
+    use crate::types::simplify::MAX_CACHED_UNIONS;
+    use crate::types::simplify::UnionsCache;
+    use crate::types::simplify::collapse_gradual_tuples;
+    use crate::types::simplify::collapse_literals;
+    use crate::types::simplify::flatten_and_dedup;
+    use crate::types::simplify::simplify_tuples;
+    use crate::types::simplify::try_collapse;
     use crate::types::simplify::unions;
+    use crate::types::simplify::unions_with_literals;
+    use crate::types::stdlib::Stdlib;
+    use crate::types::tuple::Tuple;
     use crate::types::types::NeverStyle;
+    use crate::types::types::TArgs;
     use crate::types::types::Type;
 
+    /// A `Stdlib` where only the non-generic `builtins` and `types` classes are available.
+    fn fake_stdlib() -> Stdlib {
+        Stdlib::new(PythonVersion::default(), &|module, name| {
+            // Classes are identified by module, so give each one its own.
+            (module == ModuleName::builtins() || module == ModuleName::types())
+                .then(|| (fake_class(name, name, 0), fake_tparams(Vec::new())))
+        })
+    }
+
+    fn lit_int(x: i64) -> Type {
+        Type::Literal(Lit::Int(LitInt::new(x)))
+    }
+
+    #[test]
+    fn test_dedup_callables() {
+        let callable = |name: Option<&'static str>| {
+            Type::Callable(Box::new(Callable::list(
+                ParamList::new(vec![Param::PosOnly(
+                    name.map(Name::new_static),
+                    Type::None,
+                    Required::Required,
+                )]),
+                Type::None,
+            )))
+        };
+        assert_eq!(unions(vec![callable(None), callable(None)]), callable(None));
+        // Positional-only parameter names are irrelevant.
+        assert!(matches!(
+            unions(vec![callable(Some("x")), callable(Some("y"))]),
+            Type::Callable(_)
+        ));
+        // But other differences are kept.
+        let other = Type::Callable(Box::new(Callable::list(
+            ParamList::new(vec![Param::Pos(
+                Name::new_static("x"),
+                Type::None,
+                Required::Required,
+            )]),
+            Type::None,
+        )));
+        assert_eq!(
+            unions(vec![callable(Some("x")), other.clone()]),
+            Type::Union(vec![callable(Some("x")), other])
+        );
+    }
+
+    #[test]
+    fn test_large_int_literals_with_int() {
+        let stdlib = fake_stdlib();
+        let int = stdlib.int().clone().to_type();
+        let mut xs = (0..1000)
+            .map(|i| Type::Literal(Lit::Int(LitInt::from_ast(&Int::from(u64::MAX - i)))))
+            .collect::<Vec<_>>();
+        xs.push(int.clone());
+        assert_eq!(unions_with_literals(xs, &stdlib), int);
+    }
+
+    #[test]
+    fn test_nested_optional() {
+        let stdlib = fake_stdlib();
+        let int = stdlib.int().clone().to_type();
+        let optional_int = Type::Union(vec![int.clone(), Type::None]);
+        // `Optional[Optional[int]]` is `int | None | None`, which keeps a single `None`.
+        assert_eq!(
+            unions_with_literals(vec![optional_int.clone(), Type::None], &stdlib),
+            optional_int
+        );
+        // `Any` doesn't absorb `None`: `Optional[Any]` must stay a union so that
+        // `x is not None` can still narrow it.
+        let any = Type::any_explicit();
+        assert_eq!(
+            unions_with_literals(vec![any.clone(), Type::None], &stdlib),
+            Type::Union(vec![any, Type::None])
+        );
+    }
+
+    #[test]
+    fn test_unions_unresolved() {
+        let unresolved = Type::any_unresolved();
+        // The other members are kept, as is the marker.
+        let xs = vec![Type::None, unresolved.clone(), Type::any_implicit()];
+        let res = unions(xs);
+        assert_eq!(res, Type::Union(vec![unresolved, Type::None]));
+        assert!(res.is_degraded_by_unresolved_import());
+        // Without an unresolved member, the other `Any`s are left alone.
+        assert_eq!(
+            unions(vec![Type::None, Type::any_error(), Type::any_implicit()]),
+            Type::Union(vec![Type::any_implicit(), Type::any_error(), Type::None])
+        );
+    }
+
     #[test]
     fn test_flatten_nevers() {
         let xs = vec![
@@ -208,5 +457,516 @@ mod tests {
         ];
         let res = unions(xs);
         assert_eq!(res, Type::never());
+
+        // The style of the result doesn't depend on the order of the members.
+        let xs = vec![
+            Type::Never(NeverStyle::NoReturn),
+            Type::Never(NeverStyle::Never),
+        ];
+        assert_eq!(unions(xs), Type::Never(NeverStyle::Never));
+        let xs = vec![
+            Type::Never(NeverStyle::NoReturn),
+            Type::Never(NeverStyle::NoReturn),
+        ];
+        assert_eq!(unions(xs), Type::Never(NeverStyle::Never));
+
+        let stdlib = fake_stdlib();
+        let int = stdlib.int().clone().to_type();
+        assert_eq!(unions(vec![int.clone(), Type::never()]), int);
+        let xs = vec![
+            Type::Never(NeverStyle::Never),
+            Type::Never(NeverStyle::NoReturn),
+            int.clone(),
+        ];
+        assert_eq!(unions(xs.clone()), int);
+        assert_eq!(unions_with_literals(xs, &stdlib), int);
+    }
+
+    #[test]
+    fn test_int_literals_with_bool() {
+        let stdlib = fake_stdlib();
+        let bool = stdlib.bool().clone().to_type();
+
+        // `0` and `1` are `int` literals, so `bool` must not absorb them.
+        assert_eq!(
+            unions_with_literals(vec![lit_int(0), bool.clone()], &stdlib),
+            Type::Union(vec![lit_int(0), bool.clone()])
+        );
+        assert_eq!(
+            unions_with_literals(vec![bool.clone(), lit_int(1), lit_int(0)], &stdlib),
+            Type::Union(vec![lit_int(0), lit_int(1), bool.clone()])
+        );
+        assert_eq!(
+            unions_with_literals(
+                vec![
+                    Type::Literal(Lit::Bool(true)),
+                    lit_int(0),
+                    Type::Literal(Lit::Bool(false)),
+                ],
+                &stdlib
+            ),
+            Type::Union(vec![lit_int(0), bool])
+        );
+    }
+
+    #[test]
+    fn test_enum_literals_from_two_enums() {
+        // Classes are identified by module, so give each one its own.
+        fn enum_class(name: &str) -> ClassType {
+            ClassType::new(fake_class(name, name, 0), TArgs::default())
+        }
+        fn member(class: &ClassType, name: &'static str) -> Type {
+            Type::Literal(Lit::Enum(Box::new(LitEnum {
+                class: class.clone(),
+                member: Name::new_static(name),
+                ty: Type::None,
+            })))
+        }
+        let stdlib = fake_stdlib();
+        let a = enum_class("A");
+        let b = enum_class("B");
+        let a_ty = a.clone().to_type();
+        let b_ty = b.clone().to_type();
+        let mut expected = vec![a_ty.clone(), b_ty.clone()];
+        expected.sort();
+        assert_eq!(
+            unions_with_literals(
+                vec![
+                    member(&a, "X"),
+                    member(&a, "Y"),
+                    a_ty.clone(),
+                    member(&b, "Z"),
+                    b_ty.clone(),
+                ],
+                &stdlib
+            ),
+            Type::Union(expected)
+        );
+        // Without `B` itself, only the `A` literals are absorbed.
+        assert_eq!(
+            unions_with_literals(
+                vec![member(&a, "X"), a_ty.clone(), member(&b, "Z")],
+                &stdlib
+            ),
+            unions(vec![member(&b, "Z"), a_ty])
+        );
+    }
+
+    #[test]
+    fn test_simplify_unbounded_tuple_union() {
+        let stdlib = fake_stdlib();
+        let int = stdlib.int().clone().to_type();
+        let str = stdlib.str().clone().to_type();
+        let tuple = Tuple::unbounded(Type::Union(vec![
+            int.clone(),
+            Type::Union(vec![str.clone(), int.clone()]),
+        ]));
+        assert_eq!(
+            simplify_tuples(tuple),
+            Type::Tuple(Tuple::unbounded(unions(vec![int, str])))
+        );
+    }
+
+    #[test]
+    fn test_dedup_none_type() {
+        let stdlib = fake_stdlib();
+        let none_type = stdlib.none_type().clone().to_type();
+        assert_eq!(
+            unions_with_literals(vec![Type::None, none_type.clone()], &stdlib),
+            Type::None
+        );
+        // `type[None]` is distinct from `None`.
+        let type_none = Type::type_form(Type::None);
+        assert_eq!(
+            unions_with_literals(vec![type_none.clone(), none_type], &stdlib),
+            Type::Union(vec![type_none, Type::None])
+        );
+    }
+
+    #[test]
+    fn test_skip_collapse_literals() {
+        let stdlib = fake_stdlib();
+        let int = stdlib.int().clone().to_type();
+        let str = stdlib.str().clone().to_type();
+        let bool = stdlib.bool().clone().to_type();
+        let cases = vec![
+            vec![int.clone(), str.clone()],
+            vec![Type::Union(vec![int.clone(), Type::None]), bool.clone()],
+            vec![lit_int(1), int.clone()],
+            vec![Type::LiteralString, str.clone()],
+            vec![
+                Type::Union(vec![Type::Literal(Lit::Bool(true)), str.clone()]),
+                Type::Literal(Lit::Bool(false)),
+            ],
+        ];
+        for xs in cases {
+            let (mut expected, _) = flatten_and_dedup(xs.clone(), Some(&stdlib));
+            collapse_literals(&mut expected, &stdlib);
+            collapse_gradual_tuples(&mut expected);
+            let expected = try_collapse(expected).unwrap_or_else(Type::Union);
+            assert_eq!(unions_with_literals(xs, &stdlib), expected);
+        }
+    }
+
+    #[test]
+    fn test_collapse_gradual_tuples() {
+        let stdlib = fake_stdlib();
+        let int = stdlib.int().clone().to_type();
+        let str = stdlib.str().clone().to_type();
+        let any_tuple = Type::Tuple(Tuple::unbounded(Type::any_explicit()));
+        let concrete = Type::Tuple(Tuple::concrete(vec![int.clone(), str.clone()]));
+        assert_eq!(
+            unions_with_literals(vec![any_tuple.clone(), concrete.clone()], &stdlib),
+            any_tuple
+        );
+        assert_eq!(
+            unions_with_literals(
+                vec![
+                    concrete.clone(),
+                    Type::Tuple(Tuple::unbounded(int.clone())),
+                    any_tuple.clone(),
+                    Type::None
+                ],
+                &stdlib
+            ),
+            Type::Union(vec![any_tuple, Type::None])
+        );
+        // A partially gradual tuple doesn't absorb anything.
+        let partial = Type::Tuple(Tuple::concrete(vec![Type::any_explicit(), int]));
+        assert_eq!(
+            unions_with_literals(vec![partial.clone(), concrete.clone()], &stdlib),
+            Type::Union(vec![concrete, partial])
+        );
+    }
+
+    /// A tiny deterministic pseudo-random generator, so the property test below is reproducible.
+    struct Rng(u64);
+
+    impl Rng {
+        /// A number in `0..=n`.
+        fn upto(&mut self, n: usize) -> usize {
+            // xorshift64
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % (n as u64 + 1)) as usize
+        }
+    }
+
+    /// Wrap random runs of `elts` in `*tuple[...]`, which `simplify_tuples` should flatten away.
+    fn group(elts: &[Type], rng: &mut Rng) -> Vec<Type> {
+        let mut res = Vec::new();
+        let mut rest = elts;
+        while !rest.is_empty() {
+            let (chunk, tail) = rest.split_at(rng.upto(rest.len() - 1) + 1);
+            if chunk.len() > 1 || rng.upto(1) == 0 {
+                res.push(Type::Unpack(Box::new(Type::Tuple(Tuple::Concrete(
+                    chunk.to_vec(),
+                )))));
+            } else {
+                res.extend(chunk.iter().cloned());
+            }
+            rest = tail;
+        }
+        res
+    }
+
+    /// A randomly nested tuple containing `left`, then `core`, then `right`, in that order.
+    fn nest(left: &[Type], core: Type, right: &[Type], rng: &mut Rng, depth: usize) -> Type {
+        if depth == 0 {
+            return Type::Tuple(Tuple::unpacked(group(left, rng), core, group(right, rng)));
+        }
+        let i = rng.upto(left.len());
+        let j = rng.upto(right.len());
+        Type::Tuple(Tuple::unpacked(
+            group(&left[..i], rng),
+            nest(&left[i..], core, &right[..j], rng, depth - 1),
+            group(&right[j..], rng),
+        ))
+    }
+
+    #[test]
+    fn test_simplify_tuples_preserves_order() {
+        let stdlib = fake_stdlib();
+        let str = stdlib.str().clone().to_type();
+        let mut rng = Rng(0x2545F4914F6CDD1D);
+        for _ in 0..1000 {
+            // Distinguishable elements, so any reordering would be visible.
+            let n = rng.upto(8);
+            let elts = (0..n as i64).map(lit_int).collect::<Vec<_>>();
+            let split = rng.upto(n);
+            let (left, right) = elts.split_at(split);
+            let depth = rng.upto(4);
+
+            // When the innermost tuple is concrete, everything flattens into one concrete tuple.
+            let Type::Tuple(tuple) = nest(
+                left,
+                Type::Tuple(Tuple::Concrete(Vec::new())),
+                right,
+                &mut rng,
+                depth,
+            ) else {
+                unreachable!()
+            };
+            assert_eq!(
+                simplify_tuples(tuple),
+                Type::Tuple(Tuple::Concrete(elts.clone()))
+            );
+
+            // When it is unbounded, the elements on either side keep their order.
+            let unbounded = Type::Tuple(Tuple::unbounded(str.clone()));
+            let Type::Tuple(tuple) = nest(left, unbounded.clone(), right, &mut rng, depth) else {
+                unreachable!()
+            };
+            let expected = if elts.is_empty() {
+                unbounded
+            } else {
+                Type::Tuple(Tuple::unpacked(left.to_vec(), unbounded, right.to_vec()))
+            };
+            assert_eq!(simplify_tuples(tuple), expected);
+        }
+    }
+
+    #[test]
+    fn test_simplify_deeply_nested_tuple() {
+        let stdlib = fake_stdlib();
+        let int = stdlib.int().clone().to_type();
+        let str = stdlib.str().clone().to_type();
+        let depth = 100_000;
+        let mut tuple = Tuple::unbounded(str.clone());
+        for _ in 0..depth {
+            tuple = Tuple::unpacked(vec![int.clone()], Type::Tuple(tuple), vec![Type::None]);
+        }
+        assert_eq!(
+            simplify_tuples(tuple),
+            Type::Tuple(Tuple::unpacked(
+                vec![int; depth],
+                Type::Tuple(Tuple::unbounded(str)),
+                vec![Type::None; depth]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_simplify_non_empty_homogeneous_tuple() {
+        let stdlib = fake_stdlib();
+        let int = stdlib.int().clone().to_type();
+        let str = stdlib.str().clone().to_type();
+        let ints = Type::Tuple(Tuple::unbounded(int.clone()));
+        // `tuple[int, *tuple[int, ...]]` is canonical, and is not `tuple[int, ...]`.
+        let canonical = Type::Tuple(Tuple::unpacked(vec![int.clone()], ints.clone(), Vec::new()));
+        assert_eq!(canonical.clone().canonicalize_unbounded_runs(), canonical);
+        // `tuple[*tuple[int, ...], int]` means the same thing, but `simplify_tuples` keeps the
+        // order it was written in.
+        let written = Tuple::unpacked(Vec::new(), ints.clone(), vec![int.clone()]);
+        assert_eq!(
+            simplify_tuples(written.clone()),
+            Type::Tuple(written.clone())
+        );
+        assert_eq!(
+            Type::Tuple(written).canonicalize_unbounded_runs(),
+            canonical
+        );
+        // Only the elements directly after the unbounded run can move.
+        assert_eq!(
+            Type::Tuple(Tuple::unpacked(
+                vec![str.clone()],
+                ints.clone(),
+                vec![int.clone(), str.clone(), int.clone()]
+            ))
+            .canonicalize_unbounded_runs(),
+            Type::Tuple(Tuple::unpacked(
+                vec![str.clone(), int.clone()],
+                ints,
+                vec![str, int]
+            ))
+        );
+    }
+    #[test]
+    fn test_simplify_gradual_middle() {
+        let stdlib = fake_stdlib();
+        let int = stdlib.int().clone().to_type();
+        let str = stdlib.str().clone().to_type();
+        let any = Type::any_explicit();
+        let gradual = Type::Tuple(Tuple::unbounded(any.clone()));
+        // `tuple[int, *tuple[Any, ...], str]` is already canonical.
+        let canonical = Type::Tuple(Tuple::unpacked(
+            vec![int.clone()],
+            gradual.clone(),
+            vec![str.clone()],
+        ));
+        assert_eq!(
+            simplify_tuples(Tuple::unpacked(
+                vec![int.clone()],
+                gradual.clone(),
+                vec![str.clone()]
+            )),
+            canonical
+        );
+        // An explicit `Unpack` around the middle is dropped.
+        assert_eq!(
+            simplify_tuples(Tuple::Unpacked(Box::new((
+                vec![int.clone()],
+                Type::Unpack(Box::new(gradual.clone())),
+                vec![str.clone()]
+            )))),
+            canonical
+        );
+        assert_eq!(
+            simplify_tuples(Tuple::Unpacked(Box::new((
+                Vec::new(),
+                Type::Unpack(Box::new(gradual.clone())),
+                Vec::new()
+            )))),
+            gradual
+        );
+        // Gradual elements after the middle move into the prefix, like any other repeated element.
+        assert_eq!(
+            simplify_tuples(Tuple::unpacked(
+                vec![any.clone()],
+                gradual.clone(),
+                vec![any.clone(), int.clone()]
+            )),
+            Type::Tuple(Tuple::unpacked(vec![any.clone(), any], gradual, vec![int]))
+        );
+    }
+
+    #[test]
+    fn test_unions_cache() {
+        let stdlib = fake_stdlib();
+        let cache = UnionsCache::default();
+        let small = vec![lit_int(1), stdlib.int().clone().to_type()];
+        let mut large = (0..10).map(lit_int).collect::<Vec<_>>();
+        large.push(Type::None);
+        large.push(stdlib.int().clone().to_type());
+        large.push(lit_int(3));
+        for xs in [small, large] {
+            let expected = unions_with_literals(xs.clone(), &stdlib);
+            for _ in 0..3 {
+                assert_eq!(cache.unions_with_literals(xs.clone(), &stdlib), expected);
+            }
+        }
+        assert_eq!(
+            cache.unions_with_literals((0..10).map(lit_int).collect(), &stdlib),
+            Type::Union((0..10).map(lit_int).collect())
+        );
+        // Filling the cache past its capacity starts over, without changing any results.
+        for i in 0..(MAX_CACHED_UNIONS as i64 * 2) {
+            let xs = (i..i + 10).map(lit_int).collect::<Vec<_>>();
+            assert_eq!(
+                cache.unions_with_literals(xs.clone(), &stdlib),
+                Type::Union(xs)
+            );
+        }
+        assert!(cache.0.lock().len() <= MAX_CACHED_UNIONS);
+        // Inputs that differ only in order or duplicates share an entry.
+        cache.0.lock().clear();
+        let mut xs = (0..10).map(lit_int).collect::<Vec<_>>();
+        xs.push(stdlib.int().clone().to_type());
+        let expected = cache.unions_with_literals(xs.clone(), &stdlib);
+        xs.reverse();
+        xs.push(lit_int(4));
+        assert_eq!(cache.unions_with_literals(xs, &stdlib), expected);
+        assert_eq!(cache.0.lock().len(), 1);
+    }
+
+    /// A rough benchmark of `UnionsCache` against unioning from scratch. Run it with
+    /// `cargo test --release bench_unions_cache -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_unions_cache() {
+        let stdlib = fake_stdlib();
+        let cache = UnionsCache::default();
+        let mut xs = (0..50).map(lit_int).collect::<Vec<_>>();
+        xs.push(stdlib.int().clone().to_type());
+        xs.push(Type::None);
+        const ITERATIONS: usize = 10000;
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            unions_with_literals(xs.clone(), &stdlib);
+        }
+        let uncached = start.elapsed();
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            cache.unions_with_literals(xs.clone(), &stdlib);
+        }
+        let cached = start.elapsed();
+        eprintln!("{ITERATIONS} unions: uncached {uncached:?}, cached {cached:?}");
+        assert_eq!(
+            cache.unions_with_literals(xs.clone(), &stdlib),
+            unions_with_literals(xs, &stdlib)
+        );
+    }
+
+    #[test]
+    fn test_dedup_equal_literals() {
+        let class = ClassType::new(fake_class("E", "E", 0), TArgs::default());
+        let lit_enum = || {
+            Type::Literal(Lit::Enum(Box::new(LitEnum {
+                class: class.clone(),
+                member: Name::new_static("X"),
+                ty: Type::None,
+            })))
+        };
+        let big = 1u64 << 63;
+        // Each pair is the same literal, constructed separately.
+        let pairs = [
+            (
+                Type::Literal(Lit::Str("x".into())),
+                Type::Literal(Lit::Str(String::from("x").into_boxed_str())),
+            ),
+            (
+                lit_int(1),
+                Type::Literal(Lit::Int(LitInt::from_ast(&Int::from(1u64)))),
+            ),
+            (
+                Type::Literal(Lit::Int(LitInt::from_ast(&Int::from(big)))),
+                Type::Literal(Lit::Int(LitInt::new(i64::MIN).negate())),
+            ),
+            (
+                lit_int(i64::MIN),
+                Type::Literal(Lit::Int(LitInt::from_ast(&Int::from(big)).negate())),
+            ),
+            (
+                Type::Literal(Lit::Bool(true)),
+                Type::Literal(Lit::Bool(true)),
+            ),
+            (
+                Type::Literal(Lit::Bytes(b"x".to_vec().into_boxed_slice())),
+                Type::Literal(Lit::Bytes(Box::new(*b"x"))),
+            ),
+            (lit_enum(), lit_enum()),
+        ];
+        for (x, y) in pairs {
+            assert_eq!(unions(vec![x.clone(), y]), x);
+        }
+    }
+
+    #[test]
+    fn test_dedup_type_of_class() {
+        let stdlib = fake_stdlib();
+        let int = stdlib.int().clone().to_type();
+        let type_int = Type::type_form(int.clone());
+        assert_eq!(
+            unions_with_literals(vec![type_int.clone(), type_int.clone()], &stdlib),
+            type_int
+        );
+        assert_eq!(
+            unions_with_literals(
+                vec![
+                    type_int.clone(),
+                    Type::Union(vec![int.clone(), type_int.clone()])
+                ],
+                &stdlib
+            ),
+            Type::Union(vec![int.clone(), type_int.clone()])
+        );
+        // Wrapping in `type[...]` must keep `int` and `type[int]` apart.
+        assert_eq!(
+            flatten_and_dedup(vec![type_int.clone(), int.clone()], Some(&stdlib))
+                .0
+                .len(),
+            2
+        );
     }
 }
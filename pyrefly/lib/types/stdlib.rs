@@ -258,6 +258,11 @@ impl Stdlib {
         Self::primitive(&self.none_type)
     }
 
+    /// Whether `cls` is `NoneType`. Unlike `none_type`, this is safe to call while bootstrapping.
+    pub fn is_none_type(&self, cls: &ClassType) -> bool {
+        self.none_type.as_ref().is_ok_and(|x| x == cls)
+    }
+
     pub fn int(&self) -> &ClassType {
         Self::primitive(&self.int)
     }
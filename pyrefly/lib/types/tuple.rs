@@ -12,7 +12,9 @@ use pyrefly_derive::TypeEq;
 use pyrefly_derive::Visit;
 use pyrefly_derive::VisitMut;
 use pyrefly_util::display::commas_iter;
+use thiserror::Error;
 
+use crate::types::simplify::unions;
 use crate::types::types::Type;
 
 /*
@@ -35,6 +37,35 @@ pub enum Tuple {
     Unpacked(Box<(Vec<Type>, Type, Vec<Type>)>),
 }
 
+/// One element of a tuple being built with `Tuple::from_spec`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(not(test), expect(dead_code))] // Logically part of the API
+pub enum ElementSpec {
+    /// A single element, e.g. `int` in `tuple[int, str]`.
+    Fixed(Type),
+    /// Any number of elements of the given type, as in `tuple[int, ...]`.
+    Variadic(Type),
+    /// An unpacked tuple or `TypeVarTuple`, e.g. `*Ts` in `tuple[int, *Ts]`.
+    /// The type is the one being unpacked, without the surrounding `Type::Unpack`.
+    Unpack(Type),
+}
+
+/// Why a list of `ElementSpec` doesn't describe a valid tuple. These are also the messages of the
+/// errors reported for an invalid `tuple[...]` annotation.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TupleSpecError {
+    #[error("`...` is only allowed as the second of two elements: `tuple[T, ...]`")]
+    MisplacedEllipsis,
+    #[error("`...` cannot be used with an unpacked `TypeVarTuple` or tuple")]
+    EllipsisWithUnpack,
+    #[error("Only one unbounded type is allowed to be unpacked")]
+    ExtraUnpack,
+    #[error("Expected a tuple or `TypeVarTuple`, got `{0}`")]
+    NotUnpackable(Type),
+    #[error("`TypeVarTuple` must be unpacked")]
+    TypeVarTupleNotUnpacked,
+}
+
 impl Default for Tuple {
     fn default() -> Self {
         Self::Concrete(Vec::new())
@@ -60,6 +91,159 @@ impl Tuple {
         Self::Unpacked(Box::new((prefix, middle, suffix)))
     }
 
+    /// Build a tuple from its elements, following the same rules as a `tuple[...]` annotation:
+    /// a variadic element must be the only one, and at most one unbounded tuple or
+    /// `TypeVarTuple` may be unpacked. Unpacked fixed-length tuples are spliced in place.
+    #[cfg_attr(not(test), expect(dead_code))] // Logically part of the API
+    pub fn from_spec(elements: Vec<ElementSpec>) -> Result<Tuple, TupleSpecError> {
+        if elements
+            .iter()
+            .any(|e| matches!(e, ElementSpec::Variadic(_)))
+        {
+            let len = elements.len();
+            let has_unpack = elements.iter().any(|e| match e {
+                ElementSpec::Unpack(_) => true,
+                ElementSpec::Fixed(t) | ElementSpec::Variadic(t) => t.is_unpack(),
+            });
+            return match elements.into_iter().next() {
+                _ if has_unpack => Err(TupleSpecError::EllipsisWithUnpack),
+                Some(ElementSpec::Variadic(t)) if len == 1 => Ok(Tuple::unbounded(t)),
+                _ => Err(TupleSpecError::MisplacedEllipsis),
+            };
+        }
+        let mut prefix = Vec::new();
+        let mut middle = None;
+        let mut suffix = Vec::new();
+        for element in elements {
+            let known = if middle.is_none() {
+                &mut prefix
+            } else {
+                &mut suffix
+            };
+            match element {
+                ElementSpec::Variadic(_) => unreachable!("handled above"),
+                ElementSpec::Fixed(t) if t.is_kind_type_var_tuple() => {
+                    return Err(TupleSpecError::TypeVarTupleNotUnpacked);
+                }
+                ElementSpec::Fixed(t) => known.push(t),
+                ElementSpec::Unpack(Type::Tuple(Tuple::Concrete(elts))) => known.extend(elts),
+                ElementSpec::Unpack(_) if middle.is_some() => {
+                    return Err(TupleSpecError::ExtraUnpack);
+                }
+                ElementSpec::Unpack(t @ Type::Tuple(Tuple::Unbounded(_))) => middle = Some(t),
+                ElementSpec::Unpack(Type::Tuple(Tuple::Unpacked(box (pre, mid, suff)))) => {
+                    prefix.extend(pre);
+                    middle = Some(mid);
+                    suffix.extend(suff);
+                }
+                ElementSpec::Unpack(t) if t.is_kind_type_var_tuple() => middle = Some(t),
+                ElementSpec::Unpack(t) => return Err(TupleSpecError::NotUnpackable(t)),
+            }
+        }
+        Ok(match middle {
+            Some(middle) => Tuple::unpacked(prefix, middle, suffix),
+            None => Tuple::concrete(prefix),
+        })
+    }
+
+    /// Whether this is the empty tuple, `tuple[()]`.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Self::Concrete(elts) if elts.is_empty())
+    }
+
+    /// Whether the length of the tuple is known, i.e. it has no unbounded or unpacked part.
+    pub fn is_fixed_length(&self) -> bool {
+        matches!(self, Self::Concrete(_))
+    }
+
+    /// The union of all the element types, `Never` for an empty tuple.
+    /// Returns `None` if the tuple contains an unpacked `TypeVarTuple`, whose elements are unknown.
+    pub fn element_type(&self) -> Option<Type> {
+        match self {
+            Self::Concrete(elts) => Some(unions(elts.clone())),
+            Self::Unbounded(elt) => Some((**elt).clone()),
+            Self::Unpacked(box (prefix, Type::Tuple(middle), suffix)) => {
+                let mut elts = prefix.clone();
+                elts.push(middle.element_type()?);
+                elts.extend(suffix.iter().cloned());
+                Some(unions(elts))
+            }
+            Self::Unpacked(_) => None,
+        }
+    }
+
+    /// The element type used when matching the tuple against `Sequence[T]` or `Iterable[T]`.
+    /// For a tuple containing an unpacked `TypeVarTuple`, only the known elements contribute,
+    /// and the `TypeVarTuple` itself must be checked separately.
+    pub fn as_sequence_element(&self) -> Type {
+        match self {
+            Self::Concrete(elts) => unions(elts.clone()),
+            Self::Unbounded(elt) => (**elt).clone(),
+            Self::Unpacked(box (prefix, middle, suffix)) => {
+                let mut elts = prefix.clone();
+                if let Type::Tuple(middle) = middle {
+                    elts.push(middle.as_sequence_element());
+                }
+                elts.extend(suffix.iter().cloned());
+                unions(elts)
+            }
+        }
+    }
+
+    /// Add an element to the front of the tuple.
+    /// Prepending to an unbounded tuple produces an unpacked tuple with a one-element prefix.
+    pub fn prepend(self, t: Type) -> Tuple {
+        match self {
+            Self::Concrete(mut elts) => {
+                elts.insert(0, t);
+                Self::Concrete(elts)
+            }
+            Self::Unbounded(_) => Self::unpacked(vec![t], Type::Tuple(self), Vec::new()),
+            Self::Unpacked(box (mut prefix, middle, suffix)) => {
+                prefix.insert(0, t);
+                Self::unpacked(prefix, middle, suffix)
+            }
+        }
+    }
+
+    /// Add an element to the end of the tuple.
+    /// Appending to an unbounded tuple produces an unpacked tuple with a one-element suffix.
+    pub fn append(self, t: Type) -> Tuple {
+        match self {
+            Self::Concrete(mut elts) => {
+                elts.push(t);
+                Self::Concrete(elts)
+            }
+            Self::Unbounded(_) => Self::unpacked(Vec::new(), Type::Tuple(self), vec![t]),
+            Self::Unpacked(box (prefix, middle, mut suffix)) => {
+                suffix.push(t);
+                Self::unpacked(prefix, middle, suffix)
+            }
+        }
+    }
+
+    /// Apply `f` to every element type, keeping the shape of the tuple. The middle of an
+    /// unpacked tuple is mapped element-wise when it is itself a tuple, and otherwise (e.g. for a
+    /// `TypeVarTuple`) passed to `f` whole. The result is normalized as by `Tuple::unpacked`.
+    pub fn map_elements(self, mut f: impl FnMut(Type) -> Type) -> Tuple {
+        fn go(tuple: Tuple, f: &mut dyn FnMut(Type) -> Type) -> Tuple {
+            match tuple {
+                Tuple::Concrete(elts) => Tuple::Concrete(elts.into_iter().map(&mut *f).collect()),
+                Tuple::Unbounded(box elt) => Tuple::unbounded(f(elt)),
+                Tuple::Unpacked(box (prefix, middle, suffix)) => {
+                    let prefix = prefix.into_iter().map(&mut *f).collect();
+                    let middle = match middle {
+                        Type::Tuple(middle) => Type::Tuple(go(middle, f)),
+                        middle => f(middle),
+                    };
+                    let suffix = suffix.into_iter().map(&mut *f).collect();
+                    Tuple::unpacked(prefix, middle, suffix)
+                }
+            }
+        }
+        go(self, &mut f)
+    }
+
     pub fn fmt_with_type<'a, D: Display + 'a>(
         &'a self,
         f: &mut fmt::Formatter<'_>,
@@ -92,3 +276,306 @@ impl Tuple {
         write!(f, "tuple[{contents}]")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pyrefly_util::uniques::UniqueFactory;
+    use ruff_python_ast::name::Name;
+
+    use crate::types::quantified::Quantified;
+    use crate::types::tuple::ElementSpec;
+    use crate::types::tuple::Tuple;
+    use crate::types::tuple::TupleSpecError;
+    use crate::types::types::Type;
+
+    #[test]
+    fn test_is_empty_is_fixed_length() {
+        let uniques = UniqueFactory::new();
+        let ts = Quantified::type_var_tuple(Name::new_static("Ts"), &uniques, None).to_type();
+        let cases = [
+            (Tuple::default(), true, true),
+            (Tuple::concrete(vec![Type::None]), false, true),
+            (Tuple::unbounded(Type::None), false, false),
+            (
+                Tuple::unpacked(
+                    vec![Type::None],
+                    Type::Tuple(Tuple::unbounded(Type::None)),
+                    Vec::new(),
+                ),
+                false,
+                false,
+            ),
+            (Tuple::unpacked(Vec::new(), ts, Vec::new()), false, false),
+        ];
+        for (tuple, empty, fixed_length) in cases {
+            assert_eq!(tuple.is_empty(), empty, "{tuple:?}");
+            assert_eq!(tuple.is_fixed_length(), fixed_length, "{tuple:?}");
+        }
+    }
+
+    #[test]
+    fn test_element_type() {
+        assert_eq!(Tuple::default().element_type(), Some(Type::never()));
+        assert_eq!(
+            Tuple::concrete(vec![Type::None, Type::LiteralString]).element_type(),
+            Some(Type::Union(vec![Type::None, Type::LiteralString]))
+        );
+        assert_eq!(
+            Tuple::unbounded(Type::None).element_type(),
+            Some(Type::None)
+        );
+        assert_eq!(
+            Tuple::unpacked(
+                vec![Type::None],
+                Type::Tuple(Tuple::unbounded(Type::LiteralString)),
+                vec![Type::None]
+            )
+            .element_type(),
+            Some(Type::Union(vec![Type::None, Type::LiteralString]))
+        );
+    }
+
+    #[test]
+    fn test_as_sequence_element() {
+        assert_eq!(Tuple::default().as_sequence_element(), Type::never());
+        assert_eq!(
+            Tuple::unbounded(Type::None).as_sequence_element(),
+            Type::None
+        );
+        // `tuple[int, *tuple[str, ...]]` matches `Sequence[int | str]`.
+        assert_eq!(
+            Tuple::unpacked(
+                vec![Type::None],
+                Type::Tuple(Tuple::unbounded(Type::LiteralString)),
+                Vec::new()
+            )
+            .as_sequence_element(),
+            Type::Union(vec![Type::None, Type::LiteralString])
+        );
+        let ellipsis = Type::Ellipsis;
+        assert_eq!(
+            Tuple::unpacked(
+                vec![Type::None],
+                Type::Tuple(Tuple::unbounded(Type::LiteralString)),
+                vec![ellipsis.clone()]
+            )
+            .as_sequence_element(),
+            Type::Union(vec![Type::None, Type::LiteralString, ellipsis.clone()])
+        );
+        let uniques = UniqueFactory::new();
+        let ts = Quantified::type_var_tuple(Name::new_static("Ts"), &uniques, None).to_type();
+        assert_eq!(
+            Tuple::unpacked(vec![Type::None], ts, vec![ellipsis.clone()]).as_sequence_element(),
+            Type::Union(vec![Type::None, ellipsis])
+        );
+    }
+
+    #[test]
+    fn test_prepend_append_concrete() {
+        let t = Tuple::concrete(vec![Type::None]);
+        assert_eq!(
+            t.clone().prepend(Type::LiteralString),
+            Tuple::concrete(vec![Type::LiteralString, Type::None])
+        );
+        assert_eq!(
+            t.append(Type::LiteralString),
+            Tuple::concrete(vec![Type::None, Type::LiteralString])
+        );
+        assert_eq!(
+            Tuple::default().append(Type::None),
+            Tuple::concrete(vec![Type::None])
+        );
+    }
+
+    #[test]
+    fn test_prepend_append_unbounded() {
+        let t = Tuple::unbounded(Type::None);
+        assert_eq!(
+            t.clone().prepend(Type::LiteralString),
+            Tuple::unpacked(
+                vec![Type::LiteralString],
+                Type::Tuple(t.clone()),
+                Vec::new()
+            )
+        );
+        assert_eq!(
+            t.clone().append(Type::LiteralString),
+            Tuple::unpacked(Vec::new(), Type::Tuple(t), vec![Type::LiteralString])
+        );
+    }
+
+    #[test]
+    fn test_prepend_append_unpacked() {
+        let middle = Type::Tuple(Tuple::unbounded(Type::None));
+        let t = Tuple::unpacked(vec![Type::None], middle.clone(), vec![Type::None]);
+        assert_eq!(
+            t.clone().prepend(Type::LiteralString),
+            Tuple::unpacked(
+                vec![Type::LiteralString, Type::None],
+                middle.clone(),
+                vec![Type::None]
+            )
+        );
+        assert_eq!(
+            t.append(Type::LiteralString),
+            Tuple::unpacked(
+                vec![Type::None],
+                middle,
+                vec![Type::None, Type::LiteralString]
+            )
+        );
+    }
+
+    #[test]
+    fn test_map_elements() {
+        let f = |t: Type| match t {
+            Type::None => Type::LiteralString,
+            t => t,
+        };
+        assert_eq!(
+            Tuple::concrete(vec![Type::None, Type::Ellipsis, Type::None]).map_elements(f),
+            Tuple::concrete(vec![
+                Type::LiteralString,
+                Type::Ellipsis,
+                Type::LiteralString
+            ])
+        );
+        assert_eq!(
+            Tuple::unbounded(Type::None).map_elements(f),
+            Tuple::unbounded(Type::LiteralString)
+        );
+        // The unpacked middle is mapped element-wise, not replaced.
+        assert_eq!(
+            Tuple::unpacked(
+                vec![Type::None],
+                Type::Tuple(Tuple::unbounded(Type::None)),
+                vec![Type::None]
+            )
+            .map_elements(f),
+            Tuple::unpacked(
+                vec![Type::LiteralString],
+                Type::Tuple(Tuple::unbounded(Type::LiteralString)),
+                vec![Type::LiteralString]
+            )
+        );
+        let uniques = UniqueFactory::new();
+        let ts = Quantified::type_var_tuple(Name::new_static("Ts"), &uniques, None).to_type();
+        assert_eq!(
+            Tuple::unpacked(vec![Type::None], ts.clone(), Vec::new()).map_elements(f),
+            Tuple::unpacked(vec![Type::LiteralString], ts, Vec::new())
+        );
+        // An unpacked tuple with nothing around its middle is normalized to the middle.
+        assert_eq!(
+            Tuple::Unpacked(Box::new((
+                Vec::new(),
+                Type::Tuple(Tuple::unbounded(Type::None)),
+                Vec::new()
+            )))
+            .map_elements(f),
+            Tuple::unbounded(Type::LiteralString)
+        );
+    }
+
+    #[test]
+    fn test_from_spec() {
+        use ElementSpec::*;
+        let uniques = UniqueFactory::new();
+        let ts = Quantified::type_var_tuple(Name::new_static("Ts"), &uniques, None).to_type();
+        let unbounded = Type::Tuple(Tuple::unbounded(Type::LiteralString));
+        assert_eq!(Tuple::from_spec(Vec::new()), Ok(Tuple::default()));
+        assert_eq!(
+            Tuple::from_spec(vec![Fixed(Type::None), Fixed(Type::LiteralString)]),
+            Ok(Tuple::concrete(vec![Type::None, Type::LiteralString]))
+        );
+        assert_eq!(
+            Tuple::from_spec(vec![Variadic(Type::None)]),
+            Ok(Tuple::unbounded(Type::None))
+        );
+        // `tuple[None, *tuple[None, str], str]` is spliced into a concrete tuple.
+        assert_eq!(
+            Tuple::from_spec(vec![
+                Fixed(Type::None),
+                Unpack(Type::tuple(vec![Type::None, Type::LiteralString])),
+                Fixed(Type::LiteralString),
+            ]),
+            Ok(Tuple::concrete(vec![
+                Type::None,
+                Type::None,
+                Type::LiteralString,
+                Type::LiteralString
+            ]))
+        );
+        assert_eq!(
+            Tuple::from_spec(vec![Fixed(Type::None), Unpack(ts.clone())]),
+            Ok(Tuple::unpacked(vec![Type::None], ts.clone(), Vec::new()))
+        );
+        assert_eq!(
+            Tuple::from_spec(vec![Unpack(unbounded.clone())]),
+            Ok(Tuple::unbounded(Type::LiteralString))
+        );
+        // Unpacking an unpacked tuple merges its prefix and suffix.
+        let inner = Type::Tuple(Tuple::unpacked(
+            vec![Type::None],
+            unbounded.clone(),
+            vec![Type::LiteralString],
+        ));
+        assert_eq!(
+            Tuple::from_spec(vec![
+                Fixed(Type::LiteralString),
+                Unpack(inner),
+                Fixed(Type::None)
+            ]),
+            Ok(Tuple::unpacked(
+                vec![Type::LiteralString, Type::None],
+                unbounded.clone(),
+                vec![Type::LiteralString, Type::None]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_from_spec_invalid() {
+        use ElementSpec::*;
+        let uniques = UniqueFactory::new();
+        let ts = Quantified::type_var_tuple(Name::new_static("Ts"), &uniques, None).to_type();
+        let unbounded = Type::Tuple(Tuple::unbounded(Type::LiteralString));
+        let cases = [
+            (
+                vec![Fixed(Type::None), Variadic(Type::None)],
+                TupleSpecError::MisplacedEllipsis,
+            ),
+            (
+                vec![Variadic(Type::None), Variadic(Type::None)],
+                TupleSpecError::MisplacedEllipsis,
+            ),
+            (
+                vec![Unpack(ts.clone()), Variadic(Type::None)],
+                TupleSpecError::EllipsisWithUnpack,
+            ),
+            (
+                vec![Variadic(Type::Unpack(Box::new(ts.clone())))],
+                TupleSpecError::EllipsisWithUnpack,
+            ),
+            (
+                vec![Unpack(ts.clone()), Unpack(unbounded.clone())],
+                TupleSpecError::ExtraUnpack,
+            ),
+            (
+                vec![Unpack(unbounded), Fixed(Type::None), Unpack(ts.clone())],
+                TupleSpecError::ExtraUnpack,
+            ),
+            (
+                vec![Unpack(Type::None)],
+                TupleSpecError::NotUnpackable(Type::None),
+            ),
+            (vec![Fixed(ts)], TupleSpecError::TypeVarTupleNotUnpacked),
+        ];
+        for (spec, err) in cases {
+            assert_eq!(Tuple::from_spec(spec.clone()), Err(err), "{spec:?}");
+        }
+        assert_eq!(
+            TupleSpecError::ExtraUnpack.to_string(),
+            "Only one unbounded type is allowed to be unpacked"
+        );
+    }
+}
@@ -6,6 +6,7 @@
  */
 
 use std::fmt;
+use std::fmt::Debug;
 use std::fmt::Display;
 use std::sync::Arc;
 
@@ -259,12 +260,21 @@ pub enum AnyStyle {
     /// There was an error, so we made up `Any`.
     /// If this `Any` is used in an error position, don't report another error.
     Error,
+    /// The value came from an import we couldn't resolve.
+    /// Treated like `Error`, but unions containing it are reported as degraded, see
+    /// `Type::is_degraded_by_unresolved_import`.
+    Unresolved,
 }
 
+/// Added once to error messages and hovers involving a type for which
+/// `Type::is_degraded_by_unresolved_import` holds.
+pub const UNRESOLVED_IMPORT_NOTE: &str =
+    "Type is degraded by an unresolved import, whose values are shown as `Unknown`";
+
 impl AnyStyle {
     pub fn propagate(self) -> Type {
         match self {
-            Self::Implicit | Self::Error => Type::Any(self),
+            Self::Implicit | Self::Error | Self::Unresolved => Type::Any(self),
             Self::Explicit => Type::Any(Self::Implicit),
         }
     }
@@ -570,7 +580,7 @@ pub enum SuperObj {
 
 // Note: The fact that Literal and LiteralString are at the front is important for
 // optimisations in `unions_with_literals`.
-#[derive(Debug, Clone, PartialEq, Eq, TypeEq, PartialOrd, Ord, Hash)]
+#[derive(Clone, PartialEq, Eq, TypeEq, PartialOrd, Ord, Hash)]
 pub enum Type {
     Literal(Lit),
     LiteralString,
@@ -655,6 +665,59 @@ pub enum Type {
     None,
 }
 
+impl Debug for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Literal(x) => f.debug_tuple("Literal").field(x).finish(),
+            Type::LiteralString => f.write_str("LiteralString"),
+            Type::Callable(x) => f.debug_tuple("Callable").field(x).finish(),
+            Type::Function(x) => f.debug_tuple("Function").field(x).finish(),
+            Type::BoundMethod(x) => f.debug_tuple("BoundMethod").field(x).finish(),
+            Type::Overload(x) => f.debug_tuple("Overload").field(x).finish(),
+            Type::Union(xs) => {
+                // Print members in sorted order, so equal unions built in different orders
+                // look the same.
+                let mut xs = xs.iter().collect::<Vec<_>>();
+                xs.sort();
+                f.debug_tuple("Union").field(&xs).finish()
+            }
+            Type::Intersect(x) => f.debug_tuple("Intersect").field(x).finish(),
+            Type::ClassDef(x) => f.debug_tuple("ClassDef").field(x).finish(),
+            Type::ClassType(x) => f.debug_tuple("ClassType").field(x).finish(),
+            Type::TypedDict(x) => f.debug_tuple("TypedDict").field(x).finish(),
+            Type::PartialTypedDict(x) => f.debug_tuple("PartialTypedDict").field(x).finish(),
+            Type::Tuple(x) => f.debug_tuple("Tuple").field(x).finish(),
+            Type::Module(x) => f.debug_tuple("Module").field(x).finish(),
+            Type::Forall(x) => f.debug_tuple("Forall").field(x).finish(),
+            Type::Var(x) => f.debug_tuple("Var").field(x).finish(),
+            Type::Quantified(x) => f.debug_tuple("Quantified").field(x).finish(),
+            Type::TypeGuard(x) => f.debug_tuple("TypeGuard").field(x).finish(),
+            Type::TypeIs(x) => f.debug_tuple("TypeIs").field(x).finish(),
+            Type::Unpack(x) => f.debug_tuple("Unpack").field(x).finish(),
+            Type::TypeVar(x) => f.debug_tuple("TypeVar").field(x).finish(),
+            Type::ParamSpec(x) => f.debug_tuple("ParamSpec").field(x).finish(),
+            Type::TypeVarTuple(x) => f.debug_tuple("TypeVarTuple").field(x).finish(),
+            Type::SpecialForm(x) => f.debug_tuple("SpecialForm").field(x).finish(),
+            Type::Concatenate(xs, x) => f.debug_tuple("Concatenate").field(xs).field(x).finish(),
+            Type::ParamSpecValue(x) => f.debug_tuple("ParamSpecValue").field(x).finish(),
+            Type::Args(x) => f.debug_tuple("Args").field(x).finish(),
+            Type::Kwargs(x) => f.debug_tuple("Kwargs").field(x).finish(),
+            Type::Type(x) => f.debug_tuple("Type").field(x).finish(),
+            Type::Ellipsis => f.write_str("Ellipsis"),
+            Type::Any(x) => f.debug_tuple("Any").field(x).finish(),
+            Type::Never(x) => f.debug_tuple("Never").field(x).finish(),
+            Type::TypeAlias(x) => f.debug_tuple("TypeAlias").field(x).finish(),
+            Type::SuperInstance(x) => f.debug_tuple("SuperInstance").field(x).finish(),
+            Type::SelfType(x) => f.debug_tuple("SelfType").field(x).finish(),
+            Type::DataclassTransformDecorator(x) => f
+                .debug_tuple("DataclassTransformDecorator")
+                .field(x)
+                .finish(),
+            Type::None => f.write_str("None"),
+        }
+    }
+}
+
 impl Visit for Type {
     fn recurse<'a>(&'a self, f: &mut dyn FnMut(&'a Self)) {
         match self {
@@ -826,7 +889,13 @@ impl Type {
     }
 
     pub fn is_error(&self) -> bool {
-        matches!(self, Type::Any(AnyStyle::Error))
+        matches!(self, Type::Any(AnyStyle::Error | AnyStyle::Unresolved))
+    }
+
+    /// Whether this type contains a union with a member from an unresolved import. Such a union
+    /// still displays its other members, but error messages and hovers note that it is degraded.
+    pub fn is_degraded_by_unresolved_import(&self) -> bool {
+        self.any(|t| matches!(t, Type::Union(xs) if xs.contains(&Type::any_unresolved())))
     }
 
     pub fn is_kind_type_var_tuple(&self) -> bool {
@@ -1149,6 +1218,10 @@ impl Type {
         Type::Any(AnyStyle::Error)
     }
 
+    pub fn any_unresolved() -> Self {
+        Type::Any(AnyStyle::Unresolved)
+    }
+
     pub fn explicit_any(self) -> Self {
         self.transform(&mut |ty| {
             if let Type::Any(style) = ty {
@@ -1190,6 +1263,25 @@ impl Type {
         })
     }
 
+    /// Move any elements at the start of the suffix of an unpacked tuple that match an unbounded
+    /// middle into the prefix, so that `tuple[*tuple[X, ...], X]` and `tuple[X, *tuple[X, ...]]`
+    /// compare equal. Both describe non-empty tuples of `X`, so they stay distinct from
+    /// `tuple[X, ...]`, which may be empty. Only used when comparing types: tuples are otherwise
+    /// kept, and displayed, in the order they were written.
+    pub fn canonicalize_unbounded_runs(self) -> Self {
+        self.transform(&mut |ty| {
+            if let Type::Tuple(Tuple::Unpacked(box (
+                prefix,
+                Type::Tuple(Tuple::Unbounded(elt)),
+                suffix,
+            ))) = ty
+            {
+                let n = suffix.iter().take_while(|x| *x == &**elt).count();
+                prefix.extend(suffix.drain(..n));
+            }
+        })
+    }
+
     /// Used prior to display to ensure unique variables don't leak out non-deterministically.
     pub fn deterministic_printing(self) -> Self {
         self.transform(&mut |ty| {
@@ -1248,7 +1340,7 @@ impl Type {
             Type::Literal(Lit::Bytes(x)) => Some(!x.is_empty()),
             Type::Literal(Lit::Str(x)) => Some(!x.is_empty()),
             Type::None => Some(false),
-            Type::Tuple(Tuple::Concrete(elements)) => Some(!elements.is_empty()),
+            Type::Tuple(tuple) if tuple.is_fixed_length() => Some(!tuple.is_empty()),
             Type::Union(options) => {
                 let mut answer = None;
                 for option in options {
@@ -1306,4 +1398,17 @@ mod tests {
         assert_eq!(str_opt.as_bool(), None);
         assert_eq!(false_opt.as_bool(), Some(false));
     }
+
+    #[test]
+    fn test_debug_union_sorted() {
+        let a = Type::Union(vec![Type::None, Type::LiteralString, Type::Ellipsis]);
+        let b = Type::Union(vec![Type::Ellipsis, Type::None, Type::LiteralString]);
+        assert_eq!(format!("{a:?}"), format!("{b:?}"));
+        assert_eq!(format!("{a:?}"), "Union([LiteralString, Ellipsis, None])");
+        // Unions nested inside other types are sorted too.
+        let nested = |x: &Type| Type::Type(Box::new(x.clone()));
+        assert_eq!(format!("{:?}", nested(&a)), format!("{:?}", nested(&b)));
+        // Display still uses the original order.
+        assert_eq!(a.to_string(), "None | LiteralString | Ellipsis");
+    }
 }
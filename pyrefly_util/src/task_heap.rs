@@ -25,12 +25,18 @@ pub struct Cancelled;
 #[derive(Clone, Dupe)]
 pub struct CancellationHandle(Arc<AtomicBool>);
 
+impl Default for CancellationHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CancellationHandle {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self(Arc::new(AtomicBool::new(false)))
     }
 
-    fn is_cancelled(&self) -> bool {
+    pub fn is_cancelled(&self) -> bool {
         self.0.load(Ordering::Relaxed)
     }
 
@@ -98,6 +104,11 @@ impl<K: Ord, V> Default for TaskHeap<K, V> {
 
 impl<K: Ord, V> TaskHeap<K, V> {
     pub fn new() -> Self {
+        Self::with_cancellation_handle(CancellationHandle::new())
+    }
+
+    /// Create a heap whose work is cancelled when `cancellation_handle` is.
+    pub fn with_cancellation_handle(cancellation_handle: CancellationHandle) -> Self {
         Self {
             inner: Mutex::new(TaskHeapInner {
                 heap: BinaryHeap::new(),
@@ -105,7 +116,7 @@ impl<K: Ord, V> TaskHeap<K, V> {
                 active_workers: 0,
                 paused_workers: 0,
             }),
-            cancellation_handle: CancellationHandle::new(),
+            cancellation_handle,
             condition: Condvar::new(),
         }
     }